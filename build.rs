@@ -0,0 +1,17 @@
+use std::env;
+use std::process::Command;
+
+/// Exposes the compiler version as `OT_RUSTC_VERSION` for the `process.runtime.version` resource label.
+fn main() {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|x| String::from_utf8(x.stdout).ok())
+        .and_then(|x| x.split_whitespace().nth(1).map(str::to_owned))
+        .unwrap_or_default();
+    println!("cargo:rustc-env=OT_RUSTC_VERSION={}", version);
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
@@ -1,5 +1,13 @@
 use ot_rs::api::context::TryFromHttpText;
+use ot_rs::api::resources::Resource;
+use ot_rs::api::trace::in_memory::InMemorySpan;
+use ot_rs::api::trace::span_context::{SpanContext, SpanId, TraceId, TraceOption, TraceState};
+use ot_rs::api::trace::span_data::SpanData;
 use ot_rs::api::trace::trace_context::TraceContext;
+use ot_rs::api::trace::{Span, SpanKind};
+use ot_rs::exporter::stdout::StdoutExporter;
+use ot_rs::sdk::trace::export::SpanExporter;
+use std::convert::TryFrom;
 use std::env;
 use warp::Filter;
 
@@ -7,20 +15,32 @@ fn main() {
     env::set_var("RUST_LOG", "info");
 
     let base = warp::header::optional::<String>("traceparent").map(|context: Option<String>| {
-        context
-            .and_then(|c| {
-                TraceContext::try_from_http_text(c.as_str())
-                    .map(|s| {
-                        format!(
-                            "t: {}, s: {}, o: {}",
-                            s.trace_id.to_string(),
-                            s.trace_id.to_string(),
-                            s.trace_option.bits()
-                        )
-                    })
-                    .ok()
-            })
-            .unwrap_or("test".to_string())
+        let parent = context.and_then(|c| TraceContext::try_from_http_text(c.as_str()).ok());
+        let trace_id = parent
+            .as_ref()
+            .map(|p| p.trace_id.clone())
+            .unwrap_or_else(TraceId::generate_random);
+        let mut resource = Resource::default();
+        resource.try_upsert("service.name", "warp").unwrap();
+
+        let mut span = InMemorySpan::new(
+            SpanContext::new(
+                &trace_id,
+                SpanId::generate_random(),
+                TraceOption::MASK_SAMPLE,
+                TraceState::empty(),
+            ),
+            &resource,
+            "GET /",
+            SpanKind::SERVER,
+            parent.as_ref().map(|p| &p.span_id),
+        );
+        span.end();
+        if let Ok(data) = SpanData::try_from(span) {
+            StdoutExporter::default().export(&[data]);
+        }
+
+        trace_id.to_base16()
     });
 
     let routes = base;
@@ -1,5 +1,7 @@
 pub mod context;
+pub mod metrics;
 pub mod registry;
 pub mod resources;
+pub mod semconv;
 pub mod trace;
 pub mod unit;
@@ -0,0 +1,213 @@
+//! [Metrics API](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/api-metrics.md)
+//!
+//! Instruments are created from a `Meter` and hand every measurement to its
+//! `MeterCore`, which is where an SDK aggregates them. All values are `f64`.
+
+use std::sync::Arc;
+
+use crate::api::registry::Variable;
+use crate::api::trace::span_context::{SpanId, TraceId};
+
+pub mod instrument;
+pub mod labels;
+
+use crate::api::metrics::instrument::{
+    Counter, Gauge, Histogram, Observer, ObserverResult, UpDownCounter,
+};
+use crate::api::metrics::labels::LabelSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InstrumentKind {
+    /// Monotonic sum, e.g. requests served.
+    Counter,
+    /// Non-monotonic sum, e.g. items in a queue.
+    UpDownCounter,
+    /// Last value set synchronously, e.g. configured pool size.
+    Gauge,
+    /// Last value reported by a callback at collection time, e.g. memory in use.
+    Observer,
+    /// Distribution of values, e.g. request latency.
+    Histogram,
+}
+
+impl InstrumentKind {
+    /// Whether values only ever add up to a growing total.
+    pub fn is_monotonic(self) -> bool {
+        self == InstrumentKind::Counter
+    }
+}
+
+/// What an instrument records: the metric `Variable` and the kind of instrument.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Descriptor {
+    variable: Variable,
+    kind: InstrumentKind,
+}
+
+impl Descriptor {
+    pub fn new(variable: Variable, kind: InstrumentKind) -> Self {
+        Self { variable, kind }
+    }
+
+    pub fn variable(&self) -> &Variable {
+        &self.variable
+    }
+
+    pub fn name(&self) -> &str {
+        self.variable.name()
+    }
+
+    pub fn kind(&self) -> InstrumentKind {
+        self.kind
+    }
+}
+
+pub type ObserverCallback = Box<dyn Fn(&mut ObserverResult) + Send + Sync>;
+
+/// Receives measurements; implemented by a metrics SDK.
+pub trait MeterCore: Send + Sync {
+    fn record(&self, descriptor: &Arc<Descriptor>, labels: &LabelSet, value: f64);
+
+    /// A measurement made while a sampled span was active, which may be kept as an exemplar.
+    fn record_in_span(
+        &self,
+        descriptor: &Arc<Descriptor>,
+        labels: &LabelSet,
+        value: f64,
+        _trace_id: &TraceId,
+        _span_id: &SpanId,
+    ) {
+        self.record(descriptor, labels, value)
+    }
+
+    /// `callback` is invoked on every collection to report the current values.
+    fn register_observer(&self, descriptor: Arc<Descriptor>, callback: ObserverCallback);
+}
+
+/// Drops everything; the core of `Meter::default()`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopMeterCore;
+
+impl MeterCore for NoopMeterCore {
+    fn record(&self, _descriptor: &Arc<Descriptor>, _labels: &LabelSet, _value: f64) {}
+
+    fn register_observer(&self, _descriptor: Arc<Descriptor>, _callback: ObserverCallback) {}
+}
+
+#[derive(Clone)]
+pub struct Meter {
+    core: Arc<dyn MeterCore>,
+}
+
+impl Meter {
+    pub fn new(core: Arc<dyn MeterCore>) -> Self {
+        Self { core }
+    }
+
+    fn descriptor(variable: Variable, kind: InstrumentKind) -> Arc<Descriptor> {
+        Arc::new(Descriptor::new(variable, kind))
+    }
+
+    pub fn new_counter(&self, variable: Variable) -> Counter {
+        Counter::new(
+            self.core.clone(),
+            Self::descriptor(variable, InstrumentKind::Counter),
+        )
+    }
+
+    pub fn new_up_down_counter(&self, variable: Variable) -> UpDownCounter {
+        UpDownCounter::new(
+            self.core.clone(),
+            Self::descriptor(variable, InstrumentKind::UpDownCounter),
+        )
+    }
+
+    pub fn new_gauge(&self, variable: Variable) -> Gauge {
+        Gauge::new(
+            self.core.clone(),
+            Self::descriptor(variable, InstrumentKind::Gauge),
+        )
+    }
+
+    pub fn new_histogram(&self, variable: Variable) -> Histogram {
+        Histogram::new(
+            self.core.clone(),
+            Self::descriptor(variable, InstrumentKind::Histogram),
+        )
+    }
+
+    pub fn new_observer<F>(&self, variable: Variable, callback: F) -> Observer
+    where
+        F: Fn(&mut ObserverResult) + Send + Sync + 'static,
+    {
+        let descriptor = Self::descriptor(variable, InstrumentKind::Observer);
+        self.core
+            .register_observer(descriptor.clone(), Box::new(callback));
+        Observer::new(descriptor)
+    }
+}
+
+impl Default for Meter {
+    fn default() -> Self {
+        Self::new(Arc::new(NoopMeterCore))
+    }
+}
+
+#[test]
+fn meter_records_through_core() {
+    use crate::api::unit::Unit;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder {
+        records: Mutex<Vec<(String, LabelSet, f64)>>,
+        observers: Mutex<Vec<(Arc<Descriptor>, ObserverCallback)>>,
+    }
+
+    impl MeterCore for Recorder {
+        fn record(&self, descriptor: &Arc<Descriptor>, labels: &LabelSet, value: f64) {
+            let name = descriptor.name().to_owned();
+            self.records
+                .lock()
+                .unwrap()
+                .push((name, labels.clone(), value));
+        }
+
+        fn register_observer(&self, descriptor: Arc<Descriptor>, callback: ObserverCallback) {
+            self.observers.lock().unwrap().push((descriptor, callback));
+        }
+    }
+
+    let core = Arc::new(Recorder::default());
+    let meter = Meter::new(core.clone());
+    let get = LabelSet::try_from_pairs(&[("method", "GET")]).unwrap();
+
+    let requests = meter.new_counter(Variable::new("requests", Unit::Dimensionless));
+    requests.add(1.0, &get);
+    requests.add(-1.0, &get);
+    let bound = requests.bind(&get);
+    bound.add(2.0);
+
+    let latency = meter.new_histogram(Variable::new("latency", Unit::Milliseconds));
+    latency.record(12.5, &LabelSet::default());
+
+    meter.new_observer(Variable::new("heap", Unit::Bytes), |x| {
+        x.observe(1024.0, &LabelSet::default())
+    });
+
+    let records = core.records.lock().unwrap();
+    assert_eq!(
+        *records,
+        vec![
+            ("requests".to_owned(), get.clone(), 1.0),
+            ("requests".to_owned(), get.clone(), 2.0),
+            ("latency".to_owned(), LabelSet::default(), 12.5),
+        ]
+    );
+
+    let observers = core.observers.lock().unwrap();
+    let mut result = ObserverResult::default();
+    (observers[0].1)(&mut result);
+    assert_eq!(observers[0].0.kind(), InstrumentKind::Observer);
+    assert_eq!(result.observations(), &[(LabelSet::default(), 1024.0)]);
+}
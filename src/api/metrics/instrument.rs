@@ -0,0 +1,179 @@
+//! Synchronous instruments, their bound forms, and observers.
+//!
+//! One-shot calls such as `Counter::add` take the labels each time; `bind` fixes
+//! the labels once for hot paths.
+
+use std::sync::Arc;
+
+use crate::api::metrics::labels::LabelSet;
+use crate::api::metrics::{Descriptor, MeterCore};
+use crate::api::trace::span_context::SpanContext;
+
+#[derive(Clone)]
+struct Instrument {
+    core: Arc<dyn MeterCore>,
+    descriptor: Arc<Descriptor>,
+}
+
+impl Instrument {
+    fn record(&self, labels: &LabelSet, value: f64) {
+        if value.is_finite() {
+            self.core.record(&self.descriptor, labels, value);
+        }
+    }
+
+    /// Offers the span's ids with the value if the span is sampled.
+    fn record_in_span(&self, labels: &LabelSet, value: f64, span: &SpanContext) {
+        if !value.is_finite() {
+            return;
+        }
+        if span.is_sample() {
+            self.core.record_in_span(
+                &self.descriptor,
+                labels,
+                value,
+                span.trace_id,
+                &span.span_id,
+            );
+        } else {
+            self.core.record(&self.descriptor, labels, value);
+        }
+    }
+}
+
+macro_rules! instrument {
+    ($(#[$doc:meta])* $name:ident, $bound:ident, $method:ident, $method_in_span:ident, $accepts:expr) => {
+        $(#[$doc])*
+        #[derive(Clone)]
+        pub struct $name(Instrument);
+
+        impl $name {
+            pub(crate) fn new(core: Arc<dyn MeterCore>, descriptor: Arc<Descriptor>) -> Self {
+                Self(Instrument { core, descriptor })
+            }
+
+            pub fn descriptor(&self) -> &Descriptor {
+                &self.0.descriptor
+            }
+
+            pub fn $method(&self, value: f64, labels: &LabelSet) {
+                if $accepts(value) {
+                    self.0.record(labels, value);
+                }
+            }
+
+            /// Same as without `_in_span`, made while `span` is active.
+            pub fn $method_in_span(&self, value: f64, labels: &LabelSet, span: &SpanContext) {
+                if $accepts(value) {
+                    self.0.record_in_span(labels, value, span);
+                }
+            }
+
+            pub fn bind(&self, labels: &LabelSet) -> $bound {
+                $bound {
+                    instrument: self.0.clone(),
+                    labels: labels.clone(),
+                }
+            }
+        }
+
+        /// Instrument with its labels fixed.
+        #[derive(Clone)]
+        pub struct $bound {
+            instrument: Instrument,
+            labels: LabelSet,
+        }
+
+        impl $bound {
+            pub fn labels(&self) -> &LabelSet {
+                &self.labels
+            }
+
+            pub fn $method(&self, value: f64) {
+                if $accepts(value) {
+                    self.instrument.record(&self.labels, value);
+                }
+            }
+
+            pub fn $method_in_span(&self, value: f64, span: &SpanContext) {
+                if $accepts(value) {
+                    self.instrument.record_in_span(&self.labels, value, span);
+                }
+            }
+        }
+    };
+}
+
+instrument!(
+    /// Monotonic sum; negative values are ignored.
+    Counter,
+    BoundCounter,
+    add,
+    add_in_span,
+    |x: f64| x >= 0.0
+);
+
+instrument!(
+    /// Sum which may go up and down.
+    UpDownCounter,
+    BoundUpDownCounter,
+    add,
+    add_in_span,
+    |_| true
+);
+
+instrument!(
+    /// Current value, replaced by each `set`.
+    Gauge,
+    BoundGauge,
+    set,
+    set_in_span,
+    |_| true
+);
+
+instrument!(
+    /// Distribution of measured values, e.g. latencies.
+    Histogram,
+    BoundHistogram,
+    record,
+    record_in_span,
+    |_| true
+);
+
+/// Values reported by an observer callback during one collection.
+#[derive(Debug, Default)]
+pub struct ObserverResult {
+    observations: Vec<(LabelSet, f64)>,
+}
+
+impl ObserverResult {
+    pub fn observe(&mut self, value: f64, labels: &LabelSet) {
+        if value.is_finite() {
+            self.observations.push((labels.clone(), value));
+        }
+    }
+
+    pub fn observations(&self) -> &[(LabelSet, f64)] {
+        &self.observations
+    }
+
+    pub fn into_observations(self) -> Vec<(LabelSet, f64)> {
+        self.observations
+    }
+}
+
+/// Handle of an asynchronous gauge whose values come from its callback.
+#[derive(Clone)]
+pub struct Observer {
+    descriptor: Arc<Descriptor>,
+}
+
+impl Observer {
+    pub(crate) fn new(descriptor: Arc<Descriptor>) -> Self {
+        Self { descriptor }
+    }
+
+    pub fn descriptor(&self) -> &Descriptor {
+        &self.descriptor
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+use crate::api::resources::{LabelName, LabelValue};
+
+/// Labels identifying one time series of a metric, kept sorted by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LabelSet(BTreeMap<LabelName, LabelValue>);
+
+impl LabelSet {
+    pub fn with(mut self, name: LabelName, value: LabelValue) -> Self {
+        self.0.insert(name, value);
+        self
+    }
+
+    /// `None` if any name or value is not a valid label.
+    pub fn try_from_pairs(pairs: &[(&str, &str)]) -> Option<Self> {
+        pairs
+            .iter()
+            .map(|(k, v)| {
+                let name = LabelName::from_str(k).ok()?;
+                let value = LabelValue::from_str(v).ok()?;
+                Some((name, value))
+            })
+            .collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LabelValue> {
+        LabelName::from_str(name).ok().and_then(|n| self.0.get(&n))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&LabelName, &LabelValue)> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromIterator<(LabelName, LabelValue)> for LabelSet {
+    fn from_iter<I: IntoIterator<Item = (LabelName, LabelValue)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+#[test]
+fn label_set_sorted_and_validated() {
+    let a = LabelSet::try_from_pairs(&[("status", "200"), ("method", "GET")]).unwrap();
+    let b = LabelSet::try_from_pairs(&[("method", "GET"), ("status", "200")]).unwrap();
+    assert_eq!(a, b);
+    let names: Vec<&str> = a.iter().map(|(k, _)| k.value()).collect();
+    assert_eq!(names, vec!["method", "status"]);
+    assert_eq!(a.get("status").map(LabelValue::value), Some("200"));
+    assert!(LabelSet::try_from_pairs(&[("", "x")]).is_none());
+}
@@ -1,9 +1,40 @@
 use crate::api::unit::Unit;
 
+/// Name, description and unit shared by every instrument recording the same metric.
+///
+/// It has no instrument kind: that belongs to each instrument, and is kept by its `Descriptor`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Variable {
     name: String,
     description: String,
     unit: Unit,
-    // FIXME: modify name to type
-    type_: String,
+}
+
+impl Variable {
+    pub fn new(name: &str, unit: Unit) -> Self {
+        Self {
+            name: name.to_owned(),
+            description: String::new(),
+            unit,
+        }
+    }
+
+    pub fn with_description(self, description: &str) -> Self {
+        Self {
+            description: description.to_owned(),
+            ..self
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn description(&self) -> &str {
+        self.description.as_str()
+    }
+
+    pub fn unit(&self) -> &Unit {
+        &self.unit
+    }
 }
@@ -1,8 +1,10 @@
-use std::collections::HashMap;
-use std::iter::FromIterator;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
-#[derive(PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub struct LabelName(String);
 
 impl LabelName {
@@ -23,7 +25,7 @@ impl FromStr for LabelName {
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub struct LabelValue(String);
 
 impl LabelValue {
@@ -44,34 +46,252 @@ impl FromStr for LabelValue {
     }
 }
 
-#[derive(Clone, Default)]
-pub struct Resource(HashMap<LabelName, LabelValue>);
+/// Typed value of a `Resource` label.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(untagged)
+)]
+pub enum ResourceValue {
+    Bool(bool),
+    Int(i64),
+    Double(f64),
+    String(String),
+}
+
+impl ResourceValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ResourceValue::String(x) => Some(x.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// Doubles are compared by their bits, so `NaN` equals itself.
+impl PartialEq for ResourceValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ResourceValue::Bool(a), ResourceValue::Bool(b)) => a == b,
+            (ResourceValue::Int(a), ResourceValue::Int(b)) => a == b,
+            (ResourceValue::Double(a), ResourceValue::Double(b)) => a.to_bits() == b.to_bits(),
+            (ResourceValue::String(a), ResourceValue::String(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ResourceValue {}
+
+impl Hash for ResourceValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            ResourceValue::Bool(x) => x.hash(state),
+            ResourceValue::Int(x) => x.hash(state),
+            ResourceValue::Double(x) => x.to_bits().hash(state),
+            ResourceValue::String(x) => x.hash(state),
+        }
+    }
+}
+
+impl fmt::Display for ResourceValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceValue::Bool(x) => write!(f, "{}", x),
+            ResourceValue::Int(x) => write!(f, "{}", x),
+            ResourceValue::Double(x) => write!(f, "{}", x),
+            ResourceValue::String(x) => f.write_str(x),
+        }
+    }
+}
+
+macro_rules! impl_from_for_resource_value {
+    ($($t:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$t> for ResourceValue {
+                fn from(value: $t) -> Self {
+                    ResourceValue::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_resource_value!(
+    bool => Bool,
+    i32 => Int,
+    i64 => Int,
+    u32 => Int,
+    f64 => Double,
+    String => String,
+    &str => String,
+);
+
+impl From<LabelValue> for ResourceValue {
+    fn from(value: LabelValue) -> Self {
+        ResourceValue::String(value.0)
+    }
+}
+
+/// How `Resource::try_merge` resolves a label set on both sides to different values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    PrimaryWins,
+    SecondaryWins,
+    ErrorOnConflict,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    /// Both sides have a schema URL and they differ.
+    SchemaUrl(String, String),
+    /// The label has different values under `MergePolicy::ErrorOnConflict`.
+    Conflict(LabelName, ResourceValue, ResourceValue),
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::SchemaUrl(a, b) => write!(f, "schema URLs differ: {} and {}", a, b),
+            MergeError::Conflict(name, a, b) => {
+                write!(f, "conflicting values of {}: {} and {}", name.value(), a, b)
+            }
+        }
+    }
+}
+
+impl Error for MergeError {}
+
+/// [Resource spec](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/resource/sdk.md):
+/// labels describing the entity producing telemetry, and the schema URL they follow.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Resource {
+    labels: BTreeMap<LabelName, ResourceValue>,
+    schema_url: Option<String>,
+}
 
 impl Resource {
-    pub fn upsert(&mut self, name: LabelName, value: LabelValue) -> &mut Self {
-        self.0.insert(name, value);
+    pub fn with_schema_url(self, schema_url: &str) -> Self {
+        Self {
+            schema_url: Some(schema_url.to_owned()),
+            ..self
+        }
+    }
+
+    pub fn schema_url(&self) -> Option<&str> {
+        self.schema_url.as_deref()
+    }
+
+    pub fn upsert<V: Into<ResourceValue>>(&mut self, name: LabelName, value: V) -> &mut Self {
+        self.labels.insert(name, value.into());
         self
     }
 
-    pub fn try_upsert(&mut self, name: &str, value: &str) -> Result<&mut Self, ()> {
-        LabelName::from_str(name).and_then(|n| {
-            LabelValue::from_str(value).map(|v| {
-                self.0.insert(n, v);
-                self
-            })
-        })
+    /// Fails if `name` is not a valid label name.
+    pub fn try_upsert<V: Into<ResourceValue>>(
+        &mut self,
+        name: &str,
+        value: V,
+    ) -> Result<&mut Self, ()> {
+        let name = LabelName::from_str(name)?;
+        Ok(self.upsert(name, value))
     }
 
+    /// Merges with `MergePolicy::SecondaryWins`. If the schema URLs differ, the result has none.
     pub fn merge(&self, other: &Self) -> Self {
-        Self(HashMap::from_iter(
-            self.0
-                .clone()
-                .into_iter()
-                .chain(other.0.clone().into_iter()),
-        ))
+        let schema_url = match (&self.schema_url, &other.schema_url) {
+            (Some(a), Some(b)) if a != b => None,
+            (a, b) => a.clone().or_else(|| b.clone()),
+        };
+        Self {
+            labels: self
+                .labels
+                .iter()
+                .chain(other.labels.iter())
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            schema_url,
+        }
+    }
+
+    /// Merges `self`, the primary, with `other`; schema URLs must match unless one is missing.
+    pub fn try_merge(&self, other: &Self, policy: MergePolicy) -> Result<Self, MergeError> {
+        if let (Some(a), Some(b)) = (&self.schema_url, &other.schema_url) {
+            if a != b {
+                return Err(MergeError::SchemaUrl(a.clone(), b.clone()));
+            }
+        }
+        let mut labels = self.labels.clone();
+        for (k, v) in other.labels.iter() {
+            match labels.get(k) {
+                Some(x) if x == v => {}
+                Some(_) if policy == MergePolicy::PrimaryWins => {}
+                Some(x) if policy == MergePolicy::ErrorOnConflict => {
+                    return Err(MergeError::Conflict(k.clone(), x.clone(), v.clone()));
+                }
+                _ => {
+                    labels.insert(k.clone(), v.clone());
+                }
+            }
+        }
+        Ok(Self {
+            labels,
+            schema_url: self.schema_url.clone().or_else(|| other.schema_url.clone()),
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ResourceValue> {
+        LabelName::from_str(name)
+            .ok()
+            .and_then(|n| self.labels.get(&n))
+    }
+
+    /// Labels sorted by name.
+    pub fn labels(&self) -> impl Iterator<Item = (&LabelName, &ResourceValue)> {
+        self.labels.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
     }
+}
 
-    pub fn labels(&self) -> impl Iterator<Item = (&LabelName, &LabelValue)> {
-        self.0.iter()
+/// Serializes as `{"labels": {..}, "schema_url": ..}`, the schema URL being `null` when unset.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Resource {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let labels: BTreeMap<&str, &ResourceValue> =
+            self.labels.iter().map(|(k, v)| (k.value(), v)).collect();
+        let mut s = serializer.serialize_struct("Resource", 2)?;
+        s.serialize_field("labels", &labels)?;
+        s.serialize_field("schema_url", &self.schema_url)?;
+        s.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Resource {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct Fields {
+            labels: BTreeMap<String, ResourceValue>,
+            #[serde(default)]
+            schema_url: Option<String>,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let mut r = Resource {
+            labels: BTreeMap::new(),
+            schema_url: fields.schema_url,
+        };
+        for (k, v) in fields.labels {
+            r.try_upsert(&k, v)
+                .map_err(|_| serde::de::Error::custom(format!("invalid resource label {}", k)))?;
+        }
+        Ok(r)
     }
 }
@@ -0,0 +1,6 @@
+//! [Semantic conventions](https://github.com/open-telemetry/opentelemetry-specification/tree/master/specification/data-semantic-conventions.md)
+//!
+//! Shared attribute names, so that the same concept is queryable across services.
+
+pub mod resource;
+pub mod trace;
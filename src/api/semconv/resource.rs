@@ -0,0 +1,74 @@
+//! [Resource conventions](https://github.com/open-telemetry/opentelemetry-specification/tree/master/specification/resource/semantic_conventions)
+//!
+//! `Resource` labels are typed, so numeric keys such as `process::PID` hold a `ResourceValue::Int`.
+
+pub mod service {
+    use crate::api::trace::key::Key;
+
+    pub const NAME: Key<String> = Key::new("service.name");
+    pub const NAMESPACE: Key<String> = Key::new("service.namespace");
+    pub const INSTANCE_ID: Key<String> = Key::new("service.instance.id");
+    pub const VERSION: Key<String> = Key::new("service.version");
+}
+
+pub mod telemetry {
+    use crate::api::trace::key::Key;
+
+    pub const SDK_NAME: Key<String> = Key::new("telemetry.sdk.name");
+    pub const SDK_LANGUAGE: Key<String> = Key::new("telemetry.sdk.language");
+    pub const SDK_VERSION: Key<String> = Key::new("telemetry.sdk.version");
+}
+
+pub mod host {
+    use crate::api::trace::key::Key;
+
+    pub const ID: Key<String> = Key::new("host.id");
+    pub const NAME: Key<String> = Key::new("host.name");
+    pub const TYPE: Key<String> = Key::new("host.type");
+    pub const ARCH: Key<String> = Key::new("host.arch");
+    pub const IMAGE_NAME: Key<String> = Key::new("host.image.name");
+    pub const IMAGE_ID: Key<String> = Key::new("host.image.id");
+    pub const IMAGE_VERSION: Key<String> = Key::new("host.image.version");
+}
+
+pub mod os {
+    use crate::api::trace::key::Key;
+
+    pub const TYPE: Key<String> = Key::new("os.type");
+    pub const DESCRIPTION: Key<String> = Key::new("os.description");
+}
+
+pub mod process {
+    use crate::api::trace::key::Key;
+
+    pub const PID: Key<i64> = Key::new("process.pid");
+    pub const EXECUTABLE_NAME: Key<String> = Key::new("process.executable.name");
+    pub const EXECUTABLE_PATH: Key<String> = Key::new("process.executable.path");
+    pub const COMMAND: Key<String> = Key::new("process.command");
+    pub const COMMAND_LINE: Key<String> = Key::new("process.command_line");
+    pub const OWNER: Key<String> = Key::new("process.owner");
+    pub const RUNTIME_NAME: Key<String> = Key::new("process.runtime.name");
+    pub const RUNTIME_VERSION: Key<String> = Key::new("process.runtime.version");
+    pub const RUNTIME_DESCRIPTION: Key<String> = Key::new("process.runtime.description");
+}
+
+pub mod container {
+    use crate::api::trace::key::Key;
+
+    pub const NAME: Key<String> = Key::new("container.name");
+    pub const ID: Key<String> = Key::new("container.id");
+    pub const IMAGE_NAME: Key<String> = Key::new("container.image.name");
+    pub const IMAGE_TAG: Key<String> = Key::new("container.image.tag");
+}
+
+pub mod k8s {
+    use crate::api::trace::key::Key;
+
+    pub const CLUSTER_NAME: Key<String> = Key::new("k8s.cluster.name");
+    pub const NAMESPACE_NAME: Key<String> = Key::new("k8s.namespace.name");
+    pub const POD_UID: Key<String> = Key::new("k8s.pod.uid");
+    pub const POD_NAME: Key<String> = Key::new("k8s.pod.name");
+    pub const CONTAINER_NAME: Key<String> = Key::new("k8s.container.name");
+    pub const NODE_NAME: Key<String> = Key::new("k8s.node.name");
+    pub const DEPLOYMENT_NAME: Key<String> = Key::new("k8s.deployment.name");
+}
@@ -0,0 +1,188 @@
+//! [Span conventions](https://github.com/open-telemetry/opentelemetry-specification/tree/master/specification/trace/semantic_conventions)
+
+/// [HTTP spans](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/trace/semantic_conventions/http.md)
+pub mod http {
+    use crate::api::trace::key::Key;
+    use crate::api::trace::Span;
+
+    pub const METHOD: Key<String> = Key::new("http.method");
+    pub const URL: Key<String> = Key::new("http.url");
+    pub const TARGET: Key<String> = Key::new("http.target");
+    pub const HOST: Key<String> = Key::new("http.host");
+    pub const SCHEME: Key<String> = Key::new("http.scheme");
+    pub const STATUS_CODE: Key<i64> = Key::new("http.status_code");
+    pub const FLAVOR: Key<String> = Key::new("http.flavor");
+    pub const USER_AGENT: Key<String> = Key::new("http.user_agent");
+    pub const ROUTE: Key<String> = Key::new("http.route");
+    pub const SERVER_NAME: Key<String> = Key::new("http.server_name");
+    pub const CLIENT_IP: Key<String> = Key::new("http.client_ip");
+    pub const REQUEST_CONTENT_LENGTH: Key<i64> = Key::new("http.request_content_length");
+    pub const RESPONSE_CONTENT_LENGTH: Key<i64> = Key::new("http.response_content_length");
+
+    pub fn set_request<'a, S: Span<'a>>(span: &mut S, method: &str, url: &str) {
+        span.set(METHOD, method);
+        span.set(URL, url);
+    }
+
+    pub fn set_server_request<'a, S: Span<'a>>(
+        span: &mut S,
+        method: &str,
+        route: &str,
+        user_agent: Option<&str>,
+    ) {
+        span.set(METHOD, method);
+        span.set(ROUTE, route);
+        if let Some(x) = user_agent {
+            span.set(USER_AGENT, x);
+        }
+    }
+
+    pub fn set_status_code<'a, S: Span<'a>>(span: &mut S, status_code: u16) {
+        span.set(STATUS_CODE, status_code);
+    }
+}
+
+/// [Database spans](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/trace/semantic_conventions/database.md)
+pub mod db {
+    use crate::api::trace::key::Key;
+    use crate::api::trace::Span;
+
+    pub const SYSTEM: Key<String> = Key::new("db.system");
+    pub const CONNECTION_STRING: Key<String> = Key::new("db.connection_string");
+    pub const USER: Key<String> = Key::new("db.user");
+    pub const NAME: Key<String> = Key::new("db.name");
+    pub const STATEMENT: Key<String> = Key::new("db.statement");
+    pub const OPERATION: Key<String> = Key::new("db.operation");
+
+    pub fn set_query<'a, S: Span<'a>>(span: &mut S, system: &str, name: &str, statement: &str) {
+        span.set(SYSTEM, system);
+        span.set(NAME, name);
+        span.set(STATEMENT, statement);
+    }
+}
+
+/// [RPC spans](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/trace/semantic_conventions/rpc.md)
+pub mod rpc {
+    use crate::api::trace::key::Key;
+    use crate::api::trace::Span;
+
+    pub const SYSTEM: Key<String> = Key::new("rpc.system");
+    pub const SERVICE: Key<String> = Key::new("rpc.service");
+    pub const METHOD: Key<String> = Key::new("rpc.method");
+    pub const GRPC_STATUS_CODE: Key<i64> = Key::new("rpc.grpc.status_code");
+
+    pub fn set_call<'a, S: Span<'a>>(span: &mut S, system: &str, service: &str, method: &str) {
+        span.set(SYSTEM, system);
+        span.set(SERVICE, service);
+        span.set(METHOD, method);
+    }
+
+    pub fn set_grpc_status_code<'a, S: Span<'a>>(span: &mut S, code: i32) {
+        span.set(GRPC_STATUS_CODE, code);
+    }
+}
+
+/// [Messaging spans](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/trace/semantic_conventions/messaging.md)
+pub mod messaging {
+    use crate::api::trace::key::Key;
+    use crate::api::trace::Span;
+
+    pub const SYSTEM: Key<String> = Key::new("messaging.system");
+    pub const DESTINATION: Key<String> = Key::new("messaging.destination");
+    /// `queue` or `topic`.
+    pub const DESTINATION_KIND: Key<String> = Key::new("messaging.destination_kind");
+    pub const PROTOCOL: Key<String> = Key::new("messaging.protocol");
+    pub const MESSAGE_ID: Key<String> = Key::new("messaging.message_id");
+    pub const CONVERSATION_ID: Key<String> = Key::new("messaging.conversation_id");
+    pub const MESSAGE_PAYLOAD_SIZE_BYTES: Key<i64> =
+        Key::new("messaging.message_payload_size_bytes");
+    /// `receive` or `process`; absent for `send`.
+    pub const OPERATION: Key<String> = Key::new("messaging.operation");
+
+    pub fn set_destination<'a, S: Span<'a>>(
+        span: &mut S,
+        system: &str,
+        destination: &str,
+        kind: &str,
+    ) {
+        span.set(SYSTEM, system);
+        span.set(DESTINATION, destination);
+        span.set(DESTINATION_KIND, kind);
+    }
+}
+
+/// [Network attributes](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/trace/semantic_conventions/span-general.md#general-network-connection-attributes)
+pub mod net {
+    use crate::api::trace::key::Key;
+    use crate::api::trace::Span;
+
+    pub const TRANSPORT: Key<String> = Key::new("net.transport");
+    pub const PEER_IP: Key<String> = Key::new("net.peer.ip");
+    pub const PEER_PORT: Key<i64> = Key::new("net.peer.port");
+    pub const PEER_NAME: Key<String> = Key::new("net.peer.name");
+    pub const HOST_IP: Key<String> = Key::new("net.host.ip");
+    pub const HOST_PORT: Key<i64> = Key::new("net.host.port");
+    pub const HOST_NAME: Key<String> = Key::new("net.host.name");
+
+    pub fn set_peer<'a, S: Span<'a>>(span: &mut S, name: &str, port: u16) {
+        span.set(PEER_NAME, name);
+        span.set(PEER_PORT, port);
+    }
+}
+
+/// [Exception events](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/trace/semantic_conventions/exceptions.md)
+pub mod exception {
+    use crate::api::trace::key::Key;
+
+    /// Name of the event carrying these attributes.
+    pub const EVENT_NAME: &str = "exception";
+    pub const TYPE: Key<String> = Key::new("exception.type");
+    pub const MESSAGE: Key<String> = Key::new("exception.message");
+    pub const STACKTRACE: Key<String> = Key::new("exception.stacktrace");
+    pub const ESCAPED: Key<bool> = Key::new("exception.escaped");
+}
+
+#[test]
+fn semconv_http_setters() {
+    use crate::api::resources::Resource;
+    use crate::api::trace::in_memory::InMemorySpan;
+    use crate::api::trace::key::Value;
+    use crate::api::trace::span_context::{SpanContext, SpanId, TraceId, TraceOption, TraceState};
+    use crate::api::trace::{Span, SpanKind};
+
+    let t = TraceId::generate_random();
+    let r = Resource::default();
+    let mut span = InMemorySpan::new(
+        SpanContext::new(
+            &t,
+            SpanId::generate_random(),
+            TraceOption::MASK_SAMPLE,
+            TraceState::empty(),
+        ),
+        &r,
+        "GET /users/:id",
+        SpanKind::SERVER,
+        None,
+    );
+    http::set_server_request(&mut span, "GET", "/users/:id", None);
+    http::set_status_code(&mut span, 404);
+    span.set(net::HOST_PORT, 8080);
+
+    assert_eq!(
+        span.attributes.get("http.method"),
+        Some(&Value::from("GET"))
+    );
+    assert_eq!(
+        span.attributes.get("http.route"),
+        Some(&Value::from("/users/:id"))
+    );
+    assert_eq!(
+        span.attributes.get("http.status_code"),
+        Some(&Value::Int64(404))
+    );
+    assert_eq!(
+        span.attributes.get("net.host.port"),
+        Some(&Value::Int64(8080))
+    );
+    assert!(!span.attributes.contains_key("http.user_agent"));
+}
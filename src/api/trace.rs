@@ -1,19 +1,27 @@
 use std::collections::HashMap;
 use std::convert::TryInto;
+use std::error::Error;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::api::resources::Resource;
-use crate::api::trace::key::Value;
+use crate::api::trace::clock::Clock;
+use crate::api::trace::exception::AsDynError;
+use crate::api::trace::key::{Key, Value};
 use crate::api::trace::span_context::SpanContext;
 use crate::api::trace::span_data::SpanData;
 use crate::api::trace::status::Status;
 
+pub mod clock;
+pub mod exception;
+pub mod hooks;
+pub mod id_generator;
 pub mod in_memory;
 pub mod key;
 pub mod propagation;
 pub mod scope;
 pub mod span_context;
 pub mod span_data;
+pub mod span_limits;
 pub mod status;
 pub mod trace_context;
 
@@ -26,7 +34,8 @@ where
     fn record_span_data(&self, value: S) -> Result<SpanData, Self::Error>;
 }
 
-enum SpanKind {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
     INTERNAL,
     SERVER,
     CLIENT,
@@ -34,9 +43,12 @@ enum SpanKind {
     CONSUMER,
 }
 
-trait Span<'a>: Sized + Sync {
+pub trait Span<'a>: Sized + Sync {
     fn start(&mut self);
 
+    /// Starts the span at a known time, e.g. when rebuilding it from logs.
+    fn start_with_timestamp(&mut self, timestamp: Timestamp);
+
     fn context(&self) -> &SpanContext;
 
     fn resource(&self) -> &Resource;
@@ -47,18 +59,51 @@ trait Span<'a>: Sized + Sync {
 
     fn add_event(&mut self, event: Event);
 
+    fn add_event_with_timestamp(&mut self, event: Event, timestamp: Timestamp);
+
     fn set_attribute(&mut self, key: String, value: Value);
 
+    /// Sets an attribute through a typed key, e.g. `span.set(HTTP_STATUS_CODE, 200)`.
+    fn set<T, V>(&mut self, key: Key<T>, value: V)
+    where
+        T: Into<Value>,
+        V: Into<T>,
+    {
+        self.set_attribute(key.name().to_owned(), value.into().into());
+    }
+
     fn update_name(&mut self, name: &str);
 
     fn set_status(&mut self, next: Status);
 
+    /// Adds an `exception` event for `error` and marks the span as failed.
+    ///
+    /// The status is `Status::from_error`: mapped from an `io::Error`, otherwise
+    /// `Status::unknown()`. Call `set_status` afterwards to choose another one.
+    fn record_error<E: Error + AsDynError + ?Sized>(&mut self, error: &E) {
+        self.add_event(exception::exception_event(error));
+        self.set_status(Status::from_error(error.as_dyn_error()));
+    }
+
     fn end(&mut self);
+
+    fn end_with_timestamp(&mut self, timestamp: Timestamp);
 }
 
 pub struct Link<'a> {
     span_context: SpanContext<'a>,
     attributes: HashMap<String, Value>,
+    dropped_attributes_count: u32,
+}
+
+impl<'a> Link<'a> {
+    pub fn new(span_context: SpanContext<'a>, attributes: HashMap<String, Value>) -> Self {
+        Self {
+            span_context,
+            attributes,
+            dropped_attributes_count: 0,
+        }
+    }
 }
 
 pub struct Event {
@@ -67,11 +112,11 @@ pub struct Event {
 }
 
 impl Event {
-    fn new(name: &str) -> Self {
+    pub fn new(name: &str) -> Self {
         Self::new_with_attributes(name, HashMap::new())
     }
 
-    fn new_with_attributes(name: &str, attributes: HashMap<String, Value>) -> Self {
+    pub fn new_with_attributes(name: &str, attributes: HashMap<String, Value>) -> Self {
         Self {
             name: name.to_string(),
             attributes,
@@ -84,81 +129,133 @@ pub struct TimedEvent {
     timestamp: Timestamp,
     name: String,
     attributes: HashMap<String, Value>,
+    pub(crate) dropped_attributes_count: u32,
 }
 
 impl TimedEvent {
-    pub fn new(event: Event) -> Self {
-        Self::new_with_timestamp(Timestamp::now(), event)
+    /// Stamps `event` with the current time of `clock`, normally the clock of its span.
+    pub fn new(clock: &dyn Clock, event: Event) -> Self {
+        Self::new_with_timestamp(clock.now(), event)
     }
 
-    fn new_with_timestamp(timestamp: Timestamp, event: Event) -> Self {
+    pub fn new_with_timestamp(timestamp: Timestamp, event: Event) -> Self {
         Self {
             timestamp,
             name: event.name,
             attributes: event.attributes,
+            dropped_attributes_count: 0,
         }
     }
+
+    pub fn timestamp(&self) -> &Timestamp {
+        &self.timestamp
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn attributes(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.attributes.iter()
+    }
+
+    pub fn dropped_attributes_count(&self) -> u32 {
+        self.dropped_attributes_count
+    }
 }
 
 /// [Timestamp spec](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/api-tracing.md#timestamp)
-#[derive(Clone)]
-pub(crate) struct Timestamp(SystemTime);
+///
+/// Conversions to numbers fail with `None` for times before the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub(crate) SystemTime);
 
 impl Timestamp {
-    fn now() -> Self {
+    pub fn now() -> Self {
         Self(SystemTime::now())
     }
-    fn as_millis(&self) -> u128 {
-        self.0
-            .duration_since(UNIX_EPOCH)
-            .as_ref()
-            .map(Duration::as_millis)
-            .unwrap()
+
+    /// `None` when the time does not fit in a `SystemTime` on this platform; the same holds
+    /// for `from_unix_micros` and `from_unix_millis`.
+    pub fn from_unix_nanos(nanos: u64) -> Option<Self> {
+        UNIX_EPOCH
+            .checked_add(Duration::from_nanos(nanos))
+            .map(Self)
     }
-    fn as_micros(&self) -> u128 {
-        self.0
-            .duration_since(UNIX_EPOCH)
-            .as_ref()
-            .map(Duration::as_micros)
-            .unwrap()
+
+    pub fn from_unix_micros(micros: u64) -> Option<Self> {
+        UNIX_EPOCH
+            .checked_add(Duration::from_micros(micros))
+            .map(Self)
     }
-    fn as_nanos(&self) -> u128 {
-        self.0
-            .duration_since(UNIX_EPOCH)
-            .as_ref()
-            .map(Duration::as_nanos)
-            .unwrap()
+
+    pub fn from_unix_millis(millis: u64) -> Option<Self> {
+        UNIX_EPOCH
+            .checked_add(Duration::from_millis(millis))
+            .map(Self)
     }
 
-    fn duration_since_as_millis(&self, other: &Self) -> Option<u128> {
+    pub fn system_time(&self) -> SystemTime {
         self.0
-            .duration_since(other.0)
-            .as_ref()
-            .map(Duration::as_millis)
-            .ok()
     }
-    fn duration_since_as_micros(&self, other: &Self) -> Option<u128> {
-        self.0
-            .duration_since(other.0)
-            .as_ref()
-            .map(Duration::as_micros)
-            .ok()
+
+    /// `None` when the result does not fit in a `SystemTime`.
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        self.0.checked_add(duration).map(Self)
     }
-    fn duration_since_as_nanos(&self, other: &Self) -> Option<u128> {
-        self.0
-            .duration_since(other.0)
-            .as_ref()
-            .map(Duration::as_nanos)
-            .ok()
+
+    pub fn since_epoch(&self) -> Option<Duration> {
+        self.0.duration_since(UNIX_EPOCH).ok()
+    }
+    pub fn as_millis(&self) -> Option<u128> {
+        self.since_epoch().as_ref().map(Duration::as_millis)
+    }
+    pub fn as_micros(&self) -> Option<u128> {
+        self.since_epoch().as_ref().map(Duration::as_micros)
+    }
+    pub fn as_nanos(&self) -> Option<u128> {
+        self.since_epoch().as_ref().map(Duration::as_nanos)
+    }
+
+    /// `None` when `other` is later than `self`.
+    pub fn duration_since(&self, other: &Self) -> Option<Duration> {
+        self.0.duration_since(other.0).ok()
+    }
+    pub fn duration_since_as_millis(&self, other: &Self) -> Option<u128> {
+        self.duration_since(other).as_ref().map(Duration::as_millis)
+    }
+    pub fn duration_since_as_micros(&self, other: &Self) -> Option<u128> {
+        self.duration_since(other).as_ref().map(Duration::as_micros)
+    }
+    pub fn duration_since_as_nanos(&self, other: &Self) -> Option<u128> {
+        self.duration_since(other).as_ref().map(Duration::as_nanos)
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        Self(time)
+    }
+}
+
+impl From<Timestamp> for SystemTime {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
     }
 }
 
 #[test]
 fn timestamp_to_primitive() {
     let a = Timestamp(UNIX_EPOCH.checked_add(Duration::from_secs(1)).unwrap());
-    assert_eq!(a.as_millis(), 1000);
-    assert_eq!(a.as_micros(), 1000_000);
-    assert_eq!(a.as_nanos(), 1000_000_000);
+    assert_eq!(a.as_millis(), Some(1000));
+    assert_eq!(a.as_micros(), Some(1000_000));
+    assert_eq!(a.as_nanos(), Some(1000_000_000));
+
+    let before_epoch = Timestamp(UNIX_EPOCH - Duration::from_secs(1));
+    assert_eq!(before_epoch.as_millis(), None);
+    assert_eq!(Timestamp::from_unix_nanos(1_000_000_000), Some(a));
+    assert_eq!(Timestamp::from_unix_micros(1_000_000), Some(a));
+    assert_eq!(Timestamp::from_unix_millis(1_000), Some(a));
 }
 
 #[test]
@@ -169,4 +266,5 @@ fn timestamp_to_duration() {
     assert_eq!(a1.duration_since_as_millis(&a2), Some(1000));
     assert_eq!(a1.duration_since_as_micros(&a2), Some(1000_000));
     assert_eq!(a1.duration_since_as_nanos(&a2), Some(1000_000_000));
+    assert_eq!(a2.duration_since_as_nanos(&a1), None);
 }
@@ -0,0 +1,81 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::api::trace::Timestamp;
+
+/// Source of span and event timestamps.
+///
+/// Start times come from `now`; everything after is `now` at the start plus the
+/// `monotonic` time elapsed since, so durations never go negative.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Timestamp;
+
+    /// Reading of a monotonic clock from an arbitrary origin.
+    fn monotonic(&self) -> Duration;
+}
+
+/// `SystemTime` for wall-clock time and `Instant` for elapsed time.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        Timestamp::now()
+    }
+
+    fn monotonic(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// Clock which only moves when told to, for tests with reproducible timestamps.
+#[derive(Debug)]
+pub struct ManualClock {
+    state: Mutex<(Timestamp, Duration)>,
+}
+
+impl ManualClock {
+    pub fn new(now: Timestamp) -> Self {
+        Self {
+            state: Mutex::new((now, Duration::from_secs(0))),
+        }
+    }
+
+    /// Moves both wall-clock and monotonic time forward.
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.0 = state.0.checked_add(duration).unwrap_or(state.0);
+        state.1 += duration;
+    }
+
+    /// Jumps wall-clock time only, as an NTP adjustment would.
+    pub fn set(&self, now: Timestamp) {
+        self.state.lock().unwrap().0 = now;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Timestamp {
+        self.state.lock().unwrap().0
+    }
+
+    fn monotonic(&self) -> Duration {
+        self.state.lock().unwrap().1
+    }
+}
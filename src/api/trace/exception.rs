@@ -0,0 +1,292 @@
+//! Recording Rust errors as [exception events](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/trace/semantic_conventions/exceptions.md).
+
+use std::any;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::collections::HashMap;
+use std::error::Error;
+
+use crate::api::semconv::trace::exception;
+use crate::api::trace::key::{Key, Value};
+use crate::api::trace::{Event, Span};
+
+/// Display of each `source()` below the recorded error, outermost first.
+pub const SOURCE_CHAIN: Key<Vec<String>> = Key::new("exception.source_chain");
+
+/// Builds the `exception` event for `error`.
+///
+/// `exception.type` is the type of `error`. It is left out for a trait object such as
+/// `&dyn Error`, whose concrete type is unknown; use `exception_event_with_type` to set it.
+pub fn exception_event<E: Error + ?Sized>(error: &E) -> Event {
+    event(error, type_name::<E>(), None)
+}
+
+/// Builds the `exception` event for `error`, with `type_name` as `exception.type`.
+pub fn exception_event_with_type<E: Error + ?Sized>(error: &E, type_name: &str) -> Event {
+    event(error, Some(type_name), None)
+}
+
+/// Builds the `exception` event for `error` with `backtrace` as `exception.stacktrace`, if it
+/// was captured.
+///
+/// Pass a backtrace captured where the error was created: one captured when recording the
+/// error shows the recording site instead.
+pub fn exception_event_with_backtrace<E: Error + ?Sized>(
+    error: &E,
+    backtrace: &Backtrace,
+) -> Event {
+    event(error, type_name::<E>(), Some(backtrace))
+}
+
+fn event<E: Error + ?Sized>(
+    error: &E,
+    type_name: Option<&str>,
+    backtrace: Option<&Backtrace>,
+) -> Event {
+    let mut attributes: HashMap<String, Value> = HashMap::new();
+    if let Some(x) = type_name {
+        attributes.insert(exception::TYPE.name().to_owned(), x.into());
+    }
+    attributes.insert(
+        exception::MESSAGE.name().to_owned(),
+        error.to_string().into(),
+    );
+
+    let mut chain = Vec::new();
+    let mut source = error.source();
+    while let Some(x) = source {
+        chain.push(x.to_string());
+        source = x.source();
+    }
+    if !chain.is_empty() {
+        attributes.insert(SOURCE_CHAIN.name().to_owned(), chain.into());
+    }
+
+    if let Some(x) = backtrace.filter(|x| x.status() == BacktraceStatus::Captured) {
+        attributes.insert(
+            exception::STACKTRACE.name().to_owned(),
+            x.to_string().into(),
+        );
+    }
+
+    Event::new_with_attributes(exception::EVENT_NAME, attributes)
+}
+
+/// `None` for trait objects, whose name is that of the trait.
+fn type_name<E: ?Sized>() -> Option<&'static str> {
+    Some(any::type_name::<E>()).filter(|x| !x.starts_with("dyn "))
+}
+
+/// An error as a `dyn Error` that can be downcast, e.g. by `Status::from_error`.
+///
+/// Implemented for every `'static` error type and for `dyn Error` trait objects.
+pub trait AsDynError {
+    fn as_dyn_error(&self) -> &(dyn Error + 'static);
+}
+
+impl<E: Error + 'static> AsDynError for E {
+    fn as_dyn_error(&self) -> &(dyn Error + 'static) {
+        self
+    }
+}
+
+impl AsDynError for dyn Error + 'static {
+    fn as_dyn_error(&self) -> &(dyn Error + 'static) {
+        self
+    }
+}
+
+impl AsDynError for dyn Error + Send + Sync + 'static {
+    fn as_dyn_error(&self) -> &(dyn Error + 'static) {
+        self
+    }
+}
+
+/// Records the error of a `Result` on a span and passes the `Result` through,
+/// e.g. `read_config().record_on_span(&mut span)?`.
+///
+/// Implemented for any error type and for boxed `dyn Error`s; `M` is inferred.
+pub trait RecordOnSpan<M> {
+    fn record_on_span<'a, S: Span<'a>>(self, span: &mut S) -> Self;
+}
+
+/// `RecordOnSpan` marker of errors implementing `Error`.
+pub enum Concrete {}
+
+/// `RecordOnSpan` marker of boxed `dyn Error`s, which do not implement `Error`.
+pub enum Boxed {}
+
+impl<T, E: Error + 'static> RecordOnSpan<Concrete> for Result<T, E> {
+    fn record_on_span<'a, S: Span<'a>>(self, span: &mut S) -> Self {
+        if let Err(e) = &self {
+            span.record_error(e);
+        }
+        self
+    }
+}
+
+impl<T> RecordOnSpan<Boxed> for Result<T, Box<dyn Error>> {
+    fn record_on_span<'a, S: Span<'a>>(self, span: &mut S) -> Self {
+        if let Err(e) = &self {
+            span.record_error(e.as_ref());
+        }
+        self
+    }
+}
+
+impl<T> RecordOnSpan<Boxed> for Result<T, Box<dyn Error + Send + Sync>> {
+    fn record_on_span<'a, S: Span<'a>>(self, span: &mut S) -> Self {
+        if let Err(e) = &self {
+            span.record_error(e.as_ref());
+        }
+        self
+    }
+}
+
+#[test]
+fn record_error_on_span() {
+    use crate::api::resources::Resource;
+    use crate::api::trace::in_memory::InMemorySpan;
+    use crate::api::trace::span_context::{SpanContext, SpanId, TraceId, TraceOption, TraceState};
+    use crate::api::trace::status::Status;
+    use crate::api::trace::SpanKind;
+    use std::fmt;
+    use std::io;
+
+    #[derive(Debug)]
+    struct ConfigError(io::Error);
+
+    impl fmt::Display for ConfigError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("cannot load config")
+        }
+    }
+
+    impl Error for ConfigError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    let t = TraceId::generate_random();
+    let r = Resource::default();
+    let mut span = InMemorySpan::new(
+        SpanContext::new(
+            &t,
+            SpanId::generate_random(),
+            TraceOption::MASK_SAMPLE,
+            TraceState::empty(),
+        ),
+        &r,
+        "load",
+        SpanKind::INTERNAL,
+        None,
+    );
+
+    let ok: Result<u8, ConfigError> = Ok(1);
+    assert_eq!(ok.record_on_span(&mut span).unwrap(), 1);
+    assert!(span.events.is_empty());
+
+    let missing = io::Error::new(io::ErrorKind::NotFound, "app.toml missing");
+    let err: Result<u8, ConfigError> = Err(ConfigError(missing));
+    assert!(err.record_on_span(&mut span).is_err());
+
+    assert_eq!(
+        span.status,
+        Status::unknown().with_description("cannot load config".to_owned())
+    );
+    let event = &span.events[0];
+    assert_eq!(event.name(), "exception");
+    let attributes: HashMap<&String, &Value> = event.attributes().collect();
+    assert_eq!(
+        attributes[&"exception.message".to_owned()],
+        &Value::from("cannot load config")
+    );
+    assert!(match attributes[&"exception.type".to_owned()] {
+        Value::String(x) => x.ends_with("ConfigError"),
+        _ => false,
+    });
+    assert_eq!(
+        attributes[&"exception.source_chain".to_owned()],
+        &Value::from(vec!["app.toml missing"])
+    );
+}
+
+#[test]
+fn record_dyn_and_boxed_errors() {
+    use crate::api::resources::Resource;
+    use crate::api::trace::in_memory::InMemorySpan;
+    use crate::api::trace::span_context::{SpanContext, SpanId, TraceId, TraceOption, TraceState};
+    use crate::api::trace::status::{CanonicalCode, Status};
+    use crate::api::trace::SpanKind;
+    use std::num::ParseIntError;
+
+    let t = TraceId::generate_random();
+    let r = Resource::default();
+    let mut span = InMemorySpan::new(
+        SpanContext::new(
+            &t,
+            SpanId::generate_random(),
+            TraceOption::MASK_SAMPLE,
+            TraceState::empty(),
+        ),
+        &r,
+        "parse",
+        SpanKind::INTERNAL,
+        None,
+    );
+    let exception_type = |span: &InMemorySpan, i: usize| {
+        span.events[i]
+            .attributes()
+            .find(|(k, _)| *k == "exception.type")
+            .map(|(_, v)| v.clone())
+    };
+
+    let parse = |x: &str| x.parse::<u8>();
+    let error = parse("x").unwrap_err();
+    let dynamic: &dyn Error = &error;
+    span.record_error(dynamic);
+    assert_eq!(exception_type(&span, 0), None);
+
+    let boxed: Result<u8, Box<dyn Error + Send + Sync>> = parse("y").map_err(Into::into);
+    assert!(boxed.record_on_span(&mut span).is_err());
+    assert_eq!(exception_type(&span, 1), None);
+
+    let boxed: Result<u8, Box<dyn Error>> = parse("z").map_err(Into::into);
+    assert!(boxed.record_on_span(&mut span).is_err());
+    assert_eq!(exception_type(&span, 2), None);
+
+    span.add_event(exception_event_with_type(
+        dynamic,
+        "std::num::ParseIntError",
+    ));
+    assert_eq!(
+        exception_type(&span, 3),
+        Some(Value::from("std::num::ParseIntError"))
+    );
+    assert!(type_name::<ParseIntError>()
+        .unwrap()
+        .ends_with("ParseIntError"));
+
+    let io = || std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+    span.record_error(&io());
+    assert_eq!(span.status.canonical_code(), CanonicalCode::NotFound);
+    let boxed: Result<u8, Box<dyn Error + Send + Sync>> = Err(io().into());
+    span.set_status(Status::ok());
+    assert!(boxed.record_on_span(&mut span).is_err());
+    assert_eq!(span.status.canonical_code(), CanonicalCode::NotFound);
+}
+
+#[test]
+fn backtraces_are_opt_in() {
+    let error = "x".parse::<u8>().unwrap_err();
+    let stacktrace = |event: Event| event.attributes.contains_key(exception::STACKTRACE.name());
+    assert!(!stacktrace(exception_event(&error)));
+    assert!(!stacktrace(exception_event_with_backtrace(
+        &error,
+        &Backtrace::disabled()
+    )));
+    assert!(stacktrace(exception_event_with_backtrace(
+        &error,
+        &Backtrace::force_capture()
+    )));
+}
@@ -0,0 +1,13 @@
+use crate::api::trace::span_context::{SpanContext, TraceId};
+use crate::api::trace::span_data::SpanData;
+
+/// Decides whether a new span is sampled; the SDK implements it for its `Sampler`.
+pub trait ShouldSample: Send + Sync {
+    fn should_sample(&self, parent: Option<&SpanContext>, trace_id: &TraceId) -> bool;
+}
+
+/// Called with the data of each sampled span as it ends; the SDK dispatches it to its span
+/// processors.
+pub trait OnEnd: Send + Sync {
+    fn on_end(&self, span: &SpanData);
+}
@@ -0,0 +1,121 @@
+use core::num::{NonZeroU128, NonZeroU64};
+use std::sync::{Arc, Mutex};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::api::trace::clock::{Clock, SystemClock};
+use crate::api::trace::span_context::{SpanId, TraceId};
+
+/// Source of new trace and span ids.
+pub trait IdGenerator: Send + Sync {
+    fn new_trace_id(&self) -> TraceId;
+
+    fn new_span_id(&self) -> SpanId;
+}
+
+/// Uniformly random ids from the thread-local generator.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RandomIdGenerator;
+
+impl IdGenerator for RandomIdGenerator {
+    fn new_trace_id(&self) -> TraceId {
+        TraceId::generate_random()
+    }
+
+    fn new_span_id(&self) -> SpanId {
+        SpanId::generate_random()
+    }
+}
+
+/// Same seed, same sequence of ids, for reproducible tests and fuzzing.
+#[derive(Debug)]
+pub struct SeededIdGenerator {
+    rng: Mutex<StdRng>,
+}
+
+impl SeededIdGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl IdGenerator for SeededIdGenerator {
+    fn new_trace_id(&self) -> TraceId {
+        TraceId::new(self.rng.lock().unwrap().gen::<NonZeroU128>())
+    }
+
+    fn new_span_id(&self) -> SpanId {
+        SpanId::new(self.rng.lock().unwrap().gen::<NonZeroU64>())
+    }
+}
+
+/// [AWS X-Ray](https://docs.aws.amazon.com/xray/latest/devguide/xray-api-sendingdata.html#xray-api-traceids)
+/// compatible ids: the high 32 bits of a trace id are its creation time in epoch seconds.
+pub struct XRayIdGenerator {
+    clock: Arc<dyn Clock>,
+}
+
+impl XRayIdGenerator {
+    pub fn new() -> Self {
+        Self {
+            clock: Arc::new(SystemClock::new()),
+        }
+    }
+
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self { clock }
+    }
+}
+
+impl Default for XRayIdGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdGenerator for XRayIdGenerator {
+    fn new_trace_id(&self) -> TraceId {
+        let secs = self
+            .clock
+            .now()
+            .since_epoch()
+            .map(|x| x.as_secs() as u32)
+            .unwrap_or(0);
+        loop {
+            let random = rand::random::<u128>() & ((1 << 96) - 1);
+            if let Some(id) = NonZeroU128::new(u128::from(secs) << 96 | random) {
+                return TraceId::new(id);
+            }
+        }
+    }
+
+    fn new_span_id(&self) -> SpanId {
+        SpanId::generate_random()
+    }
+}
+
+#[test]
+fn seeded_ids_repeat() {
+    let a = SeededIdGenerator::new(7);
+    let b = SeededIdGenerator::new(7);
+    assert_eq!(a.new_trace_id(), b.new_trace_id());
+    assert_eq!(a.new_span_id(), b.new_span_id());
+    assert_ne!(a.new_span_id(), SeededIdGenerator::new(8).new_span_id());
+}
+
+#[test]
+fn xray_trace_id_embeds_epoch_seconds() {
+    use crate::api::trace::clock::ManualClock;
+    use crate::api::trace::Timestamp;
+
+    let clock = Arc::new(ManualClock::new(
+        Timestamp::from_unix_millis(1_570_000_000_500).unwrap(),
+    ));
+    let ids = XRayIdGenerator::new().with_clock(clock);
+    let a = ids.new_trace_id();
+    assert_eq!(a.as_u128() >> 96, 1_570_000_000);
+    assert_ne!(a, ids.new_trace_id());
+}
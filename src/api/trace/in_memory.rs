@@ -1,29 +1,107 @@
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::api::resources::Resource;
+use crate::api::trace::clock::{Clock, SystemClock};
+use crate::api::trace::hooks::{OnEnd, ShouldSample};
+use crate::api::trace::id_generator::{IdGenerator, RandomIdGenerator};
 use crate::api::trace::key::Value;
-use crate::api::trace::span_context::{SpanContext, SpanId};
+use crate::api::trace::span_context::{SpanContext, SpanId, TraceId, TraceOption, TraceState};
 use crate::api::trace::span_data::SpanData;
+use crate::api::trace::span_limits::SpanLimits;
 use crate::api::trace::status::Status;
 use crate::api::trace::trace_context::TraceContext;
-use crate::api::trace::{Event, Link, Span, TimedEvent, Timestamp, Tracer};
+use crate::api::trace::{Event, Link, Span, SpanKind, TimedEvent, Timestamp, Tracer};
 
 /// [Span spec](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/api-tracing.md#span)
 pub struct InMemorySpan<'a, 'b> {
     pub(crate) context: SpanContext<'a>,
     pub(crate) resource: &'a Resource,
     pub(crate) name: String,
+    pub(crate) kind: SpanKind,
     pub(crate) start_time: Timestamp,
+    pub(crate) clock: Arc<dyn Clock>,
+    /// Set when started on `clock`, so that later timestamps are measured monotonically.
+    pub(crate) start_monotonic: Option<Duration>,
     pub(crate) finish_time: Option<Timestamp>,
     pub(crate) attributes: HashMap<String, Value>,
     pub(crate) parent_span_id: Option<&'b SpanId>,
     pub(crate) links: Vec<Link<'a>>,
     pub(crate) events: Vec<TimedEvent>,
     pub(crate) status: Status,
+    pub(crate) limits: SpanLimits,
+    pub(crate) dropped_attributes_count: u32,
+    pub(crate) dropped_events_count: u32,
+    pub(crate) dropped_links_count: u32,
+    pub(crate) on_end: Option<&'a dyn OnEnd>,
 }
 
 impl<'a, 'b> InMemorySpan<'a, 'b> {
+    /// Creates a span which starts now on the system clock.
+    pub fn new(
+        context: SpanContext<'a>,
+        resource: &'a Resource,
+        name: &str,
+        kind: SpanKind,
+        parent_span_id: Option<&'b SpanId>,
+    ) -> Self {
+        let clock = Arc::new(SystemClock::new());
+        Self {
+            context,
+            resource,
+            name: name.to_owned(),
+            kind,
+            start_time: clock.now(),
+            start_monotonic: Some(clock.monotonic()),
+            clock,
+            finish_time: None,
+            attributes: HashMap::new(),
+            parent_span_id,
+            links: Vec::new(),
+            events: Vec::new(),
+            status: Status::ok(),
+            limits: SpanLimits::default(),
+            dropped_attributes_count: 0,
+            dropped_events_count: 0,
+            dropped_links_count: 0,
+            on_end: None,
+        }
+    }
+
+    pub fn with_limits(self, limits: SpanLimits) -> Self {
+        Self { limits, ..self }
+    }
+
+    /// Called with the span data when the span ends, if it is sampled.
+    pub fn with_on_end(self, on_end: &'a dyn OnEnd) -> Self {
+        Self {
+            on_end: Some(on_end),
+            ..self
+        }
+    }
+
+    /// Takes all further timestamps from `clock`, restarting the span on it unless its start
+    /// was already set by `start_with_timestamp`.
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        let mut span = Self { clock, ..self };
+        if span.start_monotonic.is_some() {
+            span.start();
+        }
+        span
+    }
+
+    /// Wall-clock start plus monotonic elapsed time, so clock jumps cannot reorder the span.
+    fn now(&self) -> Timestamp {
+        self.start_monotonic
+            .and_then(|x| {
+                let elapsed = self.clock.monotonic().checked_sub(x)?;
+                self.start_time.checked_add(elapsed)
+            })
+            .unwrap_or_else(|| self.clock.now())
+    }
+
     fn span_duration_as_millis(&self) -> Option<u128> {
         self.finish_time
             .as_ref()
@@ -52,33 +130,39 @@ impl<'a, 'b> InMemorySpan<'a, 'b> {
         self.attributes.iter()
     }
 
-    fn get_start_epoch_time_as_millis(&self) -> u128 {
+    fn get_start_epoch_time_as_millis(&self) -> Option<u128> {
         self.start_time.as_millis()
     }
 
     fn get_finish_epoch_time_as_millis(&self) -> Option<u128> {
-        self.finish_time.as_ref().map(Timestamp::as_millis)
+        self.finish_time.as_ref().and_then(Timestamp::as_millis)
     }
 
-    fn get_start_epoch_time_as_micros(&self) -> u128 {
+    fn get_start_epoch_time_as_micros(&self) -> Option<u128> {
         self.start_time.as_micros()
     }
 
     fn get_finish_epoch_time_as_micros(&self) -> Option<u128> {
-        self.finish_time.as_ref().map(Timestamp::as_micros)
+        self.finish_time.as_ref().and_then(Timestamp::as_micros)
     }
-    fn get_start_epoch_time_as_nanos(&self) -> u128 {
+    fn get_start_epoch_time_as_nanos(&self) -> Option<u128> {
         self.start_time.as_nanos()
     }
 
     fn get_finish_epoch_time_as_nanos(&self) -> Option<u128> {
-        self.finish_time.as_ref().map(Timestamp::as_nanos)
+        self.finish_time.as_ref().and_then(Timestamp::as_nanos)
     }
 }
 
 impl<'a, 'b> Span<'a> for InMemorySpan<'a, 'b> {
     fn start(&mut self) {
-        self.start_time = Timestamp::now();
+        self.start_time = self.clock.now();
+        self.start_monotonic = Some(self.clock.monotonic());
+    }
+
+    fn start_with_timestamp(&mut self, timestamp: Timestamp) {
+        self.start_time = timestamp;
+        self.start_monotonic = None;
     }
 
     fn context(&self) -> &SpanContext {
@@ -93,16 +177,46 @@ impl<'a, 'b> Span<'a> for InMemorySpan<'a, 'b> {
         true
     }
 
-    fn add_link(&mut self, link: Link<'a>) {
+    fn add_link(&mut self, mut link: Link<'a>) {
+        if self.links.len() >= self.limits.max_links {
+            self.dropped_links_count += 1;
+            return;
+        }
+        let (attributes, dropped) = self
+            .limits
+            .limit_attributes(link.attributes, self.limits.max_attributes_per_link);
+        link.attributes = attributes;
+        link.dropped_attributes_count += dropped;
         self.links.push(link);
     }
 
     fn add_event(&mut self, event: Event) {
-        self.events.push(TimedEvent::new(event));
+        let timestamp = self.now();
+        self.add_event_with_timestamp(event, timestamp);
+    }
+
+    fn add_event_with_timestamp(&mut self, mut event: Event, timestamp: Timestamp) {
+        if self.events.len() >= self.limits.max_events {
+            self.dropped_events_count += 1;
+            return;
+        }
+        let (attributes, dropped) = self
+            .limits
+            .limit_attributes(event.attributes, self.limits.max_attributes_per_event);
+        event.attributes = attributes;
+        let mut e = TimedEvent::new_with_timestamp(timestamp, event);
+        e.dropped_attributes_count = dropped;
+        self.events.push(e);
     }
 
     fn set_attribute(&mut self, key: String, value: Value) {
-        self.attributes.insert(key, value);
+        if !self.attributes.contains_key(&key)
+            && self.attributes.len() >= self.limits.max_attributes
+        {
+            self.dropped_attributes_count += 1;
+            return;
+        }
+        self.attributes.insert(key, self.limits.truncate(value));
     }
 
     fn update_name(&mut self, name: &str) {
@@ -114,17 +228,153 @@ impl<'a, 'b> Span<'a> for InMemorySpan<'a, 'b> {
     }
 
     fn end(&mut self) {
-        self.finish_time = Some(Timestamp::now());
+        let timestamp = self.now();
+        self.end_with_timestamp(timestamp);
+    }
+
+    /// Only the first call ends the span and hands it to the `OnEnd` hook.
+    fn end_with_timestamp(&mut self, timestamp: Timestamp) {
+        if self.finish_time.is_some() {
+            return;
+        }
+        self.finish_time = Some(timestamp);
+        if let Some(on_end) = self.on_end.filter(|_| self.context.is_sample()) {
+            if let Ok(data) = SpanData::try_from(&*self) {
+                on_end.on_end(&data);
+            }
+        }
     }
 }
 
-struct InMemoryTracer<'a, 'b> {
+pub struct InMemoryTracer<'a, 'b> {
     current_trace: Option<TraceContext>,
     current_span: Option<InMemorySpan<'a, 'b>>,
     resource: Resource,
+    clock: Arc<dyn Clock>,
+    id_generator: Arc<dyn IdGenerator>,
+    limits: SpanLimits,
+    sampler: Option<Arc<dyn ShouldSample>>,
+    on_end: Option<Arc<dyn OnEnd>>,
 }
 
 impl<'a, 'b> InMemoryTracer<'a, 'b> {
+    pub fn new(resource: Resource) -> Self {
+        Self {
+            current_trace: None,
+            current_span: None,
+            resource,
+            clock: Arc::new(SystemClock::new()),
+            id_generator: Arc::new(RandomIdGenerator),
+            limits: SpanLimits::default(),
+            sampler: None,
+            on_end: None,
+        }
+    }
+
+    /// Replaces the system clock, e.g. with a `ManualClock` in tests.
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self { clock, ..self }
+    }
+
+    /// Replaces the random ids, e.g. with a `SeededIdGenerator` in tests.
+    pub fn with_id_generator(self, id_generator: Arc<dyn IdGenerator>) -> Self {
+        Self {
+            id_generator,
+            ..self
+        }
+    }
+
+    /// Limits of the spans it starts.
+    pub fn with_limits(self, limits: SpanLimits) -> Self {
+        Self { limits, ..self }
+    }
+
+    /// Decides whether the contexts from `new_root_context` and `new_child_context` are
+    /// sampled; without one they all are.
+    pub fn with_sampler(self, sampler: Arc<dyn ShouldSample>) -> Self {
+        Self {
+            sampler: Some(sampler),
+            ..self
+        }
+    }
+
+    /// Called with each sampled span it starts, when the span ends.
+    pub fn with_on_end(self, on_end: Arc<dyn OnEnd>) -> Self {
+        Self {
+            on_end: Some(on_end),
+            ..self
+        }
+    }
+
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    /// Context of a new root span, sampled as the sampler decides.
+    pub fn new_root_context(&self) -> TraceContext {
+        let trace_id = self.id_generator.new_trace_id();
+        let trace_option = self.trace_option(None, &trace_id);
+        TraceContext::new(
+            trace_id,
+            self.id_generator.new_span_id(),
+            trace_option,
+            TraceState::empty(),
+        )
+    }
+
+    /// Context of a new child span of `parent`, sampled as the sampler decides.
+    pub fn new_child_context<'c>(&self, parent: &SpanContext<'c>) -> SpanContext<'c> {
+        SpanContext::new(
+            parent.trace_id,
+            self.id_generator.new_span_id(),
+            self.trace_option(Some(parent), parent.trace_id),
+            TraceState::propagate(&parent.trace_state),
+        )
+    }
+
+    fn trace_option(&self, parent: Option<&SpanContext>, trace_id: &TraceId) -> TraceOption {
+        let sampled = match &self.sampler {
+            Some(sampler) => sampler.should_sample(parent, trace_id),
+            None => true,
+        };
+        if sampled {
+            TraceOption::MASK_SAMPLE
+        } else {
+            TraceOption::empty()
+        }
+    }
+
+    /// Context of a new root span with the given options, whatever the sampler.
+    pub fn new_trace_context(&self, trace_option: TraceOption) -> TraceContext {
+        TraceContext::new(
+            self.id_generator.new_trace_id(),
+            self.id_generator.new_span_id(),
+            trace_option,
+            TraceState::empty(),
+        )
+    }
+
+    pub fn new_span_id(&self) -> SpanId {
+        self.id_generator.new_span_id()
+    }
+
+    /// Starts a span now on this tracer's clock.
+    pub fn start_span(
+        &'a self,
+        context: SpanContext<'a>,
+        name: &str,
+        kind: SpanKind,
+        parent_span_id: Option<&'b SpanId>,
+    ) -> InMemorySpan<'a, 'b> {
+        let span = InMemorySpan::new(context, &self.resource, name, kind, parent_span_id)
+            .with_clock(self.clock.clone())
+            .with_limits(self.limits);
+        match &self.on_end {
+            Some(on_end) => span.with_on_end(on_end.as_ref()),
+            None => span,
+        }
+    }
+
     fn current_trace(&self) -> Option<&TraceContext> {
         self.current_trace.as_ref()
     }
@@ -140,3 +390,162 @@ impl<'a, 'b> Tracer<'a, InMemorySpan<'a, 'b>> for InMemoryTracer<'a, 'b> {
         SpanData::try_from(value)
     }
 }
+
+#[test]
+fn span_limits_enforced() {
+    let t = TraceId::generate_random();
+    let r = Resource::default();
+    let context = || {
+        SpanContext::new(
+            &t,
+            SpanId::generate_random(),
+            TraceOption::MASK_SAMPLE,
+            TraceState::empty(),
+        )
+    };
+    let limits = SpanLimits {
+        max_attributes: 2,
+        max_events: 1,
+        max_links: 1,
+        max_attributes_per_event: 1,
+        max_attributes_per_link: 0,
+        max_attribute_value_length: Some(3),
+    };
+    let mut span =
+        InMemorySpan::new(context(), &r, "limited", SpanKind::INTERNAL, None).with_limits(limits);
+
+    span.set_attribute("a".to_owned(), Value::String("abcdef".to_owned()));
+    span.set_attribute("b".to_owned(), Value::Int64(1));
+    span.set_attribute("c".to_owned(), Value::Int64(2));
+    span.set_attribute("b".to_owned(), Value::Int64(3));
+
+    let mut attributes = HashMap::new();
+    attributes.insert("x".to_owned(), Value::Bool(true));
+    attributes.insert("y".to_owned(), Value::Bool(false));
+    span.add_event(Event::new_with_attributes("first", attributes.clone()));
+    span.add_event(Event::new("second"));
+    span.add_link(Link::new(context(), attributes));
+    span.add_link(Link::new(context(), HashMap::new()));
+    span.end();
+
+    let data = SpanData::try_from(span).unwrap();
+    assert_eq!(data.attributes().count(), 2);
+    assert_eq!(data.dropped_attributes_count(), 1);
+    assert!(data
+        .attributes()
+        .any(|(k, v)| k == "a" && v == &Value::String("abc".to_owned())));
+    assert!(data
+        .attributes()
+        .any(|(k, v)| k == "b" && v == &Value::Int64(3)));
+    assert_eq!(data.events().count(), 1);
+    assert_eq!(data.dropped_events_count(), 1);
+    assert_eq!(data.events().next().unwrap().dropped_attributes_count(), 1);
+    assert_eq!(data.links_count(), 1);
+    assert_eq!(data.dropped_links_count(), 1);
+}
+
+#[test]
+fn explicit_and_monotonic_timestamps() {
+    let t = TraceId::generate_random();
+    let r = Resource::default();
+    let context = || {
+        SpanContext::new(
+            &t,
+            SpanId::generate_random(),
+            TraceOption::MASK_SAMPLE,
+            TraceState::empty(),
+        )
+    };
+
+    let mut replayed = InMemorySpan::new(context(), &r, "from logs", SpanKind::INTERNAL, None);
+    replayed.start_with_timestamp(Timestamp::from_unix_nanos(1_000).unwrap());
+    replayed.add_event_with_timestamp(
+        Event::new("retry"),
+        Timestamp::from_unix_nanos(1_500).unwrap(),
+    );
+    replayed.end_with_timestamp(Timestamp::from_unix_nanos(3_000).unwrap());
+    let data = SpanData::try_from(replayed).unwrap();
+    assert_eq!(data.start_time().as_nanos(), Some(1_000));
+    assert_eq!(data.end_time().as_nanos(), Some(3_000));
+    assert_eq!(
+        data.events().next().unwrap().timestamp().as_nanos(),
+        Some(1_500)
+    );
+
+    // The end is derived from the monotonic clock, never from a wall clock that may go back.
+    let mut live = InMemorySpan::new(context(), &r, "live", SpanKind::INTERNAL, None);
+    live.add_event(Event::new("halfway"));
+    live.end();
+    assert!(live.events[0].timestamp() >= &live.start_time);
+    assert!(live.finish_time.unwrap() >= *live.events[0].timestamp());
+}
+
+#[test]
+fn clock_keeps_explicit_start() {
+    let t = TraceId::generate_random();
+    let r = Resource::default();
+    let context = SpanContext::new(
+        &t,
+        SpanId::generate_random(),
+        TraceOption::MASK_SAMPLE,
+        TraceState::empty(),
+    );
+
+    let mut replayed = InMemorySpan::new(context, &r, "from logs", SpanKind::INTERNAL, None);
+    replayed.start_with_timestamp(Timestamp::from_unix_nanos(1_000).unwrap());
+    let replayed = replayed.with_clock(Arc::new(SystemClock::new()));
+    assert_eq!(replayed.start_time.as_nanos(), Some(1_000));
+}
+
+#[test]
+fn tracer_uses_injected_clock() {
+    use crate::api::trace::clock::ManualClock;
+    let clock = Arc::new(ManualClock::new(
+        Timestamp::from_unix_nanos(1_000_000).unwrap(),
+    ));
+    let tracer = InMemoryTracer::new(Resource::default()).with_clock(clock.clone());
+    let t = TraceId::generate_random();
+    let mut span = tracer.start_span(
+        SpanContext::new(
+            &t,
+            SpanId::generate_random(),
+            TraceOption::MASK_SAMPLE,
+            TraceState::empty(),
+        ),
+        "tick",
+        SpanKind::INTERNAL,
+        None,
+    );
+    clock.advance(Duration::from_millis(5));
+    span.add_event(Event::new("halfway"));
+    // A wall-clock jump backwards does not shorten the span.
+    clock.set(Timestamp::from_unix_nanos(0).unwrap());
+    clock.advance(Duration::from_millis(5));
+    span.end();
+
+    let data = SpanData::try_from(span).unwrap();
+    assert_eq!(data.start_time().as_millis(), Some(1));
+    assert_eq!(
+        data.events().next().unwrap().timestamp().as_millis(),
+        Some(6)
+    );
+    assert_eq!(data.end_time().as_millis(), Some(11));
+}
+
+#[test]
+fn tracer_uses_id_generator() {
+    use crate::api::trace::id_generator::SeededIdGenerator;
+
+    let tracer = |seed| {
+        InMemoryTracer::new(Resource::default())
+            .with_id_generator(Arc::new(SeededIdGenerator::new(seed)))
+    };
+    let (a, b) = (tracer(3), tracer(3));
+    let (ta, tb) = (
+        a.new_trace_context(TraceOption::MASK_SAMPLE),
+        b.new_trace_context(TraceOption::MASK_SAMPLE),
+    );
+    assert_eq!(ta.trace_id, tb.trace_id);
+    assert_eq!(ta.span_id, tb.span_id);
+    assert_eq!(a.new_span_id(), b.new_span_id());
+}
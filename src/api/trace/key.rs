@@ -1,19 +1,186 @@
-use crate::api::registry;
+use std::fmt;
+use std::marker::PhantomData;
 
-pub struct Key {
-    variable: registry::Variable,
+/// Attribute name whose value type is checked at compile time, e.g. `Key<i64>`.
+pub struct Key<T> {
+    name: &'static str,
+    value_type: PhantomData<fn() -> T>,
 }
 
-#[derive(Clone)]
+impl<T> Key<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            value_type: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T: Into<Value>> Key<T> {
+    pub fn value<V: Into<T>>(&self, value: V) -> KeyValue {
+        KeyValue {
+            key: self.name,
+            value: value.into().into(),
+        }
+    }
+}
+
+impl<T> Clone for Key<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Key<T> {}
+
+impl<T> fmt::Debug for Key<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Key").field(&self.name).finish()
+    }
+}
+
+/// [Attribute value spec](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/common/common.md#attributes)
+///
+/// Arrays are homogeneous, one variant per element type.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     Bool(bool),
     Int64(i64),
     UInt64(u64),
     Float64(f64),
     String(String),
+    Bytes(Vec<u8>),
+    BoolArray(Vec<bool>),
+    Int64Array(Vec<i64>),
+    UInt64Array(Vec<u64>),
+    Float64Array(Vec<f64>),
+    StringArray(Vec<String>),
+}
+
+macro_rules! impl_from_for_value {
+    ($($t:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$t> for Value {
+                fn from(value: $t) -> Self {
+                    Value::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_value!(
+    bool => Bool,
+    i8 => Int64,
+    i16 => Int64,
+    i32 => Int64,
+    i64 => Int64,
+    u8 => UInt64,
+    u16 => UInt64,
+    u32 => UInt64,
+    u64 => UInt64,
+    f32 => Float64,
+    f64 => Float64,
+    String => String,
+    &str => String,
+    Vec<u8> => Bytes,
+    &[u8] => Bytes,
+    Vec<bool> => BoolArray,
+    Vec<i64> => Int64Array,
+    Vec<u64> => UInt64Array,
+    Vec<f64> => Float64Array,
+    Vec<String> => StringArray,
+);
+
+impl From<Vec<&str>> for Value {
+    fn from(value: Vec<&str>) -> Self {
+        Value::StringArray(value.into_iter().map(str::to_owned).collect())
+    }
+}
+
+fn fmt_array<T: fmt::Display>(f: &mut fmt::Formatter<'_>, xs: &[T]) -> fmt::Result {
+    f.write_str("[")?;
+    for (i, x) in xs.iter().enumerate() {
+        if i > 0 {
+            f.write_str(",")?;
+        }
+        write!(f, "{}", x)?;
+    }
+    f.write_str("]")
 }
 
+/// Scalars print as-is, bytes as lowercase hex and arrays as JSON arrays.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(x) => write!(f, "{}", x),
+            Value::Int64(x) => write!(f, "{}", x),
+            Value::UInt64(x) => write!(f, "{}", x),
+            Value::Float64(x) => write!(f, "{}", x),
+            Value::String(x) => f.write_str(x),
+            Value::Bytes(xs) => xs.iter().try_for_each(|x| write!(f, "{:02x}", x)),
+            Value::BoolArray(xs) => fmt_array(f, xs),
+            Value::Int64Array(xs) => fmt_array(f, xs),
+            Value::UInt64Array(xs) => fmt_array(f, xs),
+            Value::Float64Array(xs) => fmt_array(f, xs),
+            Value::StringArray(xs) => {
+                let quoted: Vec<String> = xs.iter().map(|x| format!("{:?}", x)).collect();
+                fmt_array(f, &quoted)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct KeyValue {
-    key: Key,
+    key: &'static str,
     value: Value,
 }
+
+impl KeyValue {
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+}
+
+#[test]
+fn value_from_primitives() {
+    assert_eq!(Value::from(true), Value::Bool(true));
+    assert_eq!(Value::from(200), Value::Int64(200));
+    assert_eq!(Value::from(7u32), Value::UInt64(7));
+    assert_eq!(Value::from(0.5), Value::Float64(0.5));
+    assert_eq!(Value::from("GET"), Value::String("GET".to_owned()));
+    assert_eq!(Value::from(&b"\x01"[..]), Value::Bytes(vec![1]));
+    assert_eq!(
+        Value::from(vec!["a", "b"]),
+        Value::StringArray(vec!["a".to_owned(), "b".to_owned()])
+    );
+}
+
+#[test]
+fn value_display() {
+    assert_eq!(Value::from("GET").to_string(), "GET");
+    assert_eq!(Value::from(-1).to_string(), "-1");
+    assert_eq!(Value::Bytes(vec![0xde, 0xad]).to_string(), "dead");
+    assert_eq!(Value::Int64Array(vec![1, 2]).to_string(), "[1,2]");
+    assert_eq!(Value::from(vec!["a", "b"]).to_string(), r#"["a","b"]"#);
+}
+
+#[test]
+fn typed_key() {
+    const STATUS_CODE: Key<i64> = Key::new("http.status_code");
+    const METHOD: Key<String> = Key::new("http.method");
+    let kv = STATUS_CODE.value(200);
+    assert_eq!(kv.key(), "http.status_code");
+    assert_eq!(kv.value(), &Value::Int64(200));
+    assert_eq!(METHOD.value("GET").value(), &Value::from("GET"));
+}
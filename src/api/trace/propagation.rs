@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+
 use crate::api::context::{
     HttpTextExtract, HttpTextFormat, HttpTextInject, ToHttpText, TryFromHttpText,
 };
@@ -100,10 +102,10 @@ impl TryFromHttpText for TraceContext {
         }
 
         xs.get(1)
-            .and_then(|x| TraceId::try_from_base16(x))
+            .and_then(|x| TraceId::try_from(*x).ok())
             .and_then(|t| {
                 xs.get(2)
-                    .and_then(|y| SpanId::try_from_base16(y))
+                    .and_then(|y| SpanId::try_from(*y).ok())
                     .and_then(|s| {
                         xs.get(3)
                             .and_then(|z| TraceOption::try_from_base16(z))
@@ -149,7 +151,7 @@ fn http_trace_context_inject() {
     assert_eq!(m.keys().next().map(String::as_str), Some("traceparent"));
     assert_eq!(
         m.values().next().map(String::as_str),
-        Some("00-2a000000000000000000000000000000-2a00000000000000-01")
+        Some("00-0000000000000000000000000000002a-000000000000002a-01")
     )
 }
 
@@ -169,7 +171,7 @@ fn http_trace_context_extract() {
     let mut m: HashMap<String, String> = HashMap::new();
     m.insert(
         "traceparent".to_owned(),
-        "00-2a000000000000000000000000000000-2a00000000000000-01".to_owned(),
+        "00-0000000000000000000000000000002a-000000000000002a-01".to_owned(),
     );
     let a = TraceContext::extract(&m, HashMap::get);
     assert!(a.is_some());
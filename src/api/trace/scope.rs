@@ -26,13 +26,16 @@ where
 #[test]
 fn scope_test() {
     use crate::api::resources::Resource;
+    use crate::api::trace::clock::SystemClock;
     use crate::api::trace::in_memory::InMemorySpan;
     use crate::api::trace::span_context::SpanContext;
     use crate::api::trace::span_context::{SpanId, TraceId, TraceOption, TraceState};
+    use crate::api::trace::span_limits::SpanLimits;
     use crate::api::trace::status::Status;
     use crate::api::trace::trace_context::TraceContext;
-    use crate::api::trace::Timestamp;
+    use crate::api::trace::{SpanKind, Timestamp};
     use std::collections::HashMap;
+    use std::sync::Arc;
 
     let t = TraceContext::new(
         TraceId::generate_random(),
@@ -53,13 +56,21 @@ fn scope_test() {
         context: s,
         resource: &r,
         name: "test".to_owned(),
+        kind: SpanKind::INTERNAL,
         start_time: Timestamp::now(),
+        clock: Arc::new(SystemClock::new()),
+        start_monotonic: None,
         finish_time: None,
         attributes: HashMap::new(),
         parent_span_id: None,
         links: Vec::new(),
         events: Vec::new(),
         status: Status::ok(),
+        limits: SpanLimits::default(),
+        dropped_attributes_count: 0,
+        dropped_events_count: 0,
+        dropped_links_count: 0,
+        on_end: None,
     };
     let mut s = Scope::new(span, || 1);
     let a = s.run();
@@ -1,11 +1,22 @@
 use core::num::{NonZeroU128, NonZeroU64};
+use std::convert::TryFrom;
+use std::fmt;
 use std::str::FromStr;
 
 use rand;
 
 use bitflags::bitflags;
 
-#[derive(Debug, Clone)]
+/// Exactly `len` lowercase hex digits, as W3C Trace Context requires.
+fn is_lower_hex(s: &str, len: usize) -> bool {
+    s.len() == len
+        && s.bytes()
+            .all(|x| x.is_ascii_digit() || (b'a'..=b'f').contains(&x))
+}
+
+/// [W3C trace-id](https://www.w3.org/TR/trace-context/#trace-id): 16 bytes, big-endian
+/// on the wire and in hex, never all zero.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TraceId(NonZeroU128);
 
 impl TraceId {
@@ -21,34 +32,67 @@ impl TraceId {
         Self::new(rand::random::<NonZeroU128>())
     }
 
+    pub fn as_u128(&self) -> u128 {
+        self.0.get()
+    }
+
+    pub fn to_bytes(&self) -> [u8; 16] {
+        self.0.get().to_be_bytes()
+    }
+
+    /// `None` for the all-zero invalid id.
+    pub fn from_bytes(bytes: [u8; 16]) -> Option<Self> {
+        NonZeroU128::new(u128::from_be_bytes(bytes)).map(Self::new)
+    }
+
     pub fn to_base16(&self) -> String {
-        format!("{:032x}", self.0.get().to_be())
+        self.to_string()
     }
 
-    // TODO: move to TryFrom
-    pub fn try_from_base16(value: &str) -> Option<Self> {
+    /// Whether `s` is 32 lowercase hex digits and not all zero.
+    pub fn is_valid(s: &str) -> bool {
+        Self::try_from(s).is_ok()
+    }
+}
+
+impl TryFrom<&str> for TraceId {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if !is_lower_hex(value, 2 * Self::size()) {
+            return Err(());
+        }
         u128::from_str_radix(value, 16)
-            .map(u128::from_be)
             .ok()
             .and_then(NonZeroU128::new)
             .map(Self::new)
+            .ok_or(())
     }
 }
 
-impl PartialEq for TraceId {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+impl FromStr for TraceId {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl fmt::LowerHex for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
     }
 }
-impl Eq for TraceId {}
 
-impl ToString for TraceId {
-    fn to_string(&self) -> String {
-        format!("{:032x}", self.0)
+impl fmt::Display for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
     }
 }
 
-#[derive(Debug, Clone)]
+/// [W3C parent-id](https://www.w3.org/TR/trace-context/#parent-id): 8 bytes, big-endian
+/// on the wire and in hex, never all zero.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SpanId(NonZeroU64);
 
 impl SpanId {
@@ -64,29 +108,61 @@ impl SpanId {
         Self::new(rand::random::<NonZeroU64>())
     }
 
+    pub fn as_u64(&self) -> u64 {
+        self.0.get()
+    }
+
+    pub fn to_bytes(&self) -> [u8; 8] {
+        self.0.get().to_be_bytes()
+    }
+
+    /// `None` for the all-zero invalid id.
+    pub fn from_bytes(bytes: [u8; 8]) -> Option<Self> {
+        NonZeroU64::new(u64::from_be_bytes(bytes)).map(Self::new)
+    }
+
     pub fn to_base16(&self) -> String {
-        format!("{:016x}", self.0.get().to_be())
+        self.to_string()
     }
 
-    pub fn try_from_base16(value: &str) -> Option<Self> {
+    /// Whether `s` is 16 lowercase hex digits and not all zero.
+    pub fn is_valid(s: &str) -> bool {
+        Self::try_from(s).is_ok()
+    }
+}
+
+impl TryFrom<&str> for SpanId {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if !is_lower_hex(value, 2 * Self::size()) {
+            return Err(());
+        }
         u64::from_str_radix(value, 16)
-            .map(u64::from_be)
             .ok()
             .and_then(NonZeroU64::new)
             .map(Self::new)
+            .ok_or(())
     }
 }
 
-impl PartialEq for SpanId {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+impl FromStr for SpanId {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl fmt::LowerHex for SpanId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
     }
 }
-impl Eq for SpanId {}
 
-impl ToString for SpanId {
-    fn to_string(&self) -> String {
-        format!("{:016x}", self.0)
+impl fmt::Display for SpanId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
     }
 }
 
@@ -103,11 +179,14 @@ impl TraceOption {
     }
 
     pub fn to_base16(self) -> String {
-        format!("{:02x}", self.bits.to_be())
+        format!("{:02x}", self.bits)
     }
 
     pub fn try_from_base16(value: &str) -> Option<Self> {
-        u8::from_str(value).ok().and_then(Self::from_bits)
+        if !is_lower_hex(value, 2 * Self::size()) {
+            return None;
+        }
+        u8::from_str_radix(value, 16).ok().and_then(Self::from_bits)
     }
 }
 
@@ -163,9 +242,9 @@ impl Entry {
     }
 }
 
-impl ToString for Entry {
-    fn to_string(&self) -> String {
-        format!("{}={}", self.key.0, self.value.0)
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.key.0, self.value.0)
     }
 }
 
@@ -203,6 +282,66 @@ impl TraceState {
     }
 }
 
+/// Serializes as the W3C text form, e.g. a trace id as 32 lowercase hex digits.
+#[cfg(feature = "serde")]
+macro_rules! impl_serde_as_text {
+    ($t:ty, $expecting:expr, $to_text:expr, $from_text:expr) => {
+        impl serde::Serialize for $t {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&$to_text(self))
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $t {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                $from_text(s.as_str()).ok_or_else(|| {
+                    serde::de::Error::invalid_value(serde::de::Unexpected::Str(&s), &$expecting)
+                })
+            }
+        }
+    };
+}
+
+#[cfg(feature = "serde")]
+impl_serde_as_text!(
+    TraceId,
+    "32 lowercase hex digits",
+    TraceId::to_string,
+    |x| TraceId::try_from(x).ok()
+);
+
+#[cfg(feature = "serde")]
+impl_serde_as_text!(SpanId, "16 lowercase hex digits", SpanId::to_string, |x| {
+    SpanId::try_from(x).ok()
+});
+
+#[cfg(feature = "serde")]
+impl_serde_as_text!(
+    TraceOption,
+    "2 lowercase hex digits",
+    |x: &TraceOption| x.to_base16(),
+    TraceOption::try_from_base16
+);
+
+#[cfg(feature = "serde")]
+impl_serde_as_text!(
+    TraceState,
+    "a W3C tracestate header",
+    |x: &TraceState| {
+        use crate::api::context::ToHttpText;
+        x.to_http_text()
+    },
+    |x: &str| {
+        use crate::api::context::TryFromHttpText;
+        if x.is_empty() {
+            Some(TraceState::empty())
+        } else {
+            TraceState::try_from_http_text(x).ok()
+        }
+    }
+);
+
 #[derive(Debug)]
 pub struct SpanContext<'a> {
     pub trace_id: &'a TraceId,
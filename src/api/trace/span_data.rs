@@ -8,11 +8,12 @@ use crate::api::trace::span_context::{SpanContext, SpanId, TraceId, TraceOption,
 use crate::api::trace::status::Status;
 use crate::api::trace::{Link, SpanKind, TimedEvent, Timestamp};
 
-struct ImmutableSpanContext {
-    trace_id: TraceId,
-    span_id: SpanId,
-    trace_option: TraceOption,
-    trace_state: TraceState,
+#[derive(Clone)]
+pub(crate) struct ImmutableSpanContext {
+    pub(crate) trace_id: TraceId,
+    pub(crate) span_id: SpanId,
+    pub(crate) trace_option: TraceOption,
+    pub(crate) trace_state: TraceState,
 }
 
 impl<'a> From<&SpanContext<'a>> for ImmutableSpanContext {
@@ -37,9 +38,11 @@ impl<'a> From<SpanContext<'a>> for ImmutableSpanContext {
     }
 }
 
-struct ImmutableLink {
-    span_context: ImmutableSpanContext,
-    attributes: HashMap<String, Value>,
+#[derive(Clone)]
+pub(crate) struct ImmutableLink {
+    pub(crate) span_context: ImmutableSpanContext,
+    pub(crate) attributes: HashMap<String, Value>,
+    pub(crate) dropped_attributes_count: u32,
 }
 
 impl<'a> From<&Link<'a>> for ImmutableLink {
@@ -47,6 +50,7 @@ impl<'a> From<&Link<'a>> for ImmutableLink {
         Self {
             span_context: ImmutableSpanContext::from(&value.span_context),
             attributes: value.attributes.clone(),
+            dropped_attributes_count: value.dropped_attributes_count,
         }
     }
 }
@@ -56,43 +60,121 @@ impl<'a> From<Link<'a>> for ImmutableLink {
         Self {
             span_context: ImmutableSpanContext::from(&value.span_context),
             attributes: value.attributes,
+            dropped_attributes_count: value.dropped_attributes_count,
         }
     }
 }
 
 /// Immutable and Independent Span data
+#[derive(Clone)]
 pub struct SpanData {
     // should not has lifetime, maybe
-    context: ImmutableSpanContext,
-    resource: Resource,
-    parent_span_id: Option<SpanId>,
-    name: String,
-    kind: SpanKind,
-    start_time: Timestamp,
-    end_time: Timestamp,
-    attributes: HashMap<String, Value>,
-    events: Vec<TimedEvent>,
-    links: Vec<ImmutableLink>,
-    status: Status,
+    pub(crate) context: ImmutableSpanContext,
+    pub(crate) resource: Resource,
+    pub(crate) parent_span_id: Option<SpanId>,
+    pub(crate) name: String,
+    pub(crate) kind: SpanKind,
+    pub(crate) start_time: Timestamp,
+    pub(crate) end_time: Timestamp,
+    pub(crate) attributes: HashMap<String, Value>,
+    pub(crate) events: Vec<TimedEvent>,
+    pub(crate) links: Vec<ImmutableLink>,
+    pub(crate) status: Status,
+    pub(crate) dropped_attributes_count: u32,
+    pub(crate) dropped_events_count: u32,
+    pub(crate) dropped_links_count: u32,
+}
+
+impl SpanData {
+    pub fn trace_id(&self) -> &TraceId {
+        &self.context.trace_id
+    }
+
+    pub fn span_id(&self) -> &SpanId {
+        &self.context.span_id
+    }
+
+    pub fn trace_option(&self) -> TraceOption {
+        self.context.trace_option
+    }
+
+    pub fn trace_state(&self) -> &TraceState {
+        &self.context.trace_state
+    }
+
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    pub fn parent_span_id(&self) -> Option<&SpanId> {
+        self.parent_span_id.as_ref()
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn kind(&self) -> SpanKind {
+        self.kind
+    }
+
+    pub fn start_time(&self) -> &Timestamp {
+        &self.start_time
+    }
+
+    pub fn end_time(&self) -> &Timestamp {
+        &self.end_time
+    }
+
+    pub fn attributes(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.attributes.iter()
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = &TimedEvent> {
+        self.events.iter()
+    }
+
+    pub fn links_count(&self) -> usize {
+        self.links.len()
+    }
+
+    pub fn status(&self) -> &Status {
+        &self.status
+    }
+
+    pub fn dropped_attributes_count(&self) -> u32 {
+        self.dropped_attributes_count
+    }
+
+    pub fn dropped_events_count(&self) -> u32 {
+        self.dropped_events_count
+    }
+
+    pub fn dropped_links_count(&self) -> u32 {
+        self.dropped_links_count
+    }
 }
 
 impl<'a, 'b> TryFrom<&InMemorySpan<'a, 'b>> for SpanData {
     type Error = ();
 
     fn try_from(value: &InMemorySpan<'a, 'b>) -> Result<Self, Self::Error> {
-        let ft = value.finish_time.clone().ok_or(())?;
+        let ft = value.finish_time.ok_or(())?;
         Ok(Self {
             context: ImmutableSpanContext::from(&value.context),
             resource: value.resource.clone(),
             parent_span_id: value.parent_span_id.cloned(),
             name: value.name.clone(),
-            kind: SpanKind::INTERNAL,
-            start_time: value.start_time.clone(),
+            kind: value.kind,
+            start_time: value.start_time,
             end_time: ft,
             attributes: value.attributes.clone(),
             events: value.events.clone(),
             links: value.links.iter().map(ImmutableLink::from).collect(),
             status: value.status.clone(),
+            dropped_attributes_count: value.dropped_attributes_count,
+            dropped_events_count: value.dropped_events_count,
+            dropped_links_count: value.dropped_links_count,
         })
     }
 }
@@ -101,19 +183,54 @@ impl<'a, 'b> TryFrom<InMemorySpan<'a, 'b>> for SpanData {
     type Error = ();
 
     fn try_from(value: InMemorySpan<'a, 'b>) -> Result<Self, Self::Error> {
-        let ft = value.finish_time.clone().ok_or(())?;
+        let ft = value.finish_time.ok_or(())?;
         Ok(Self {
             context: ImmutableSpanContext::from(&value.context),
             resource: value.resource.clone(),
             parent_span_id: value.parent_span_id.cloned(),
             name: value.name,
-            kind: SpanKind::INTERNAL,
+            kind: value.kind,
             start_time: value.start_time,
             end_time: ft,
             attributes: value.attributes,
             events: value.events,
             links: value.links.into_iter().map(ImmutableLink::from).collect(),
             status: value.status,
+            dropped_attributes_count: value.dropped_attributes_count,
+            dropped_events_count: value.dropped_events_count,
+            dropped_links_count: value.dropped_links_count,
         })
     }
 }
+
+/// Uses the same JSON shape as the file exporter, so it needs a self-describing format.
+#[cfg(feature = "serde")]
+impl serde::Serialize for SpanData {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::exporter::json::encode_span(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SpanData {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = serde_json::Value::deserialize(deserializer)?;
+        crate::exporter::json::decode_span(&json)
+            .ok_or_else(|| serde::de::Error::custom("invalid span"))
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn span_data_serde_round_trip() {
+    use crate::exporter::test_span_data;
+
+    let r = Resource::default();
+    let s = test_span_data(&r, "job", 2, Some(1), 1_000, 3_000);
+    let j = serde_json::to_string(&s).unwrap();
+    let a: SpanData = serde_json::from_str(&j).unwrap();
+    assert_eq!(a.name(), "job");
+    assert_eq!(a.span_id(), s.span_id());
+    assert_eq!(a.parent_span_id(), s.parent_span_id());
+    assert_eq!(a.end_time(), s.end_time());
+}
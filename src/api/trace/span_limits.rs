@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::api::trace::key::Value;
+
+/// Caps on what a single span records; anything over a cap is dropped and counted.
+///
+/// [Span limits spec](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/sdk-tracing.md#limits-on-span-collections)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanLimits {
+    pub max_attributes: usize,
+    pub max_events: usize,
+    pub max_links: usize,
+    pub max_attributes_per_event: usize,
+    pub max_attributes_per_link: usize,
+    /// Longer string values are truncated to this many bytes, at a char boundary.
+    pub max_attribute_value_length: Option<usize>,
+}
+
+impl Default for SpanLimits {
+    fn default() -> Self {
+        Self {
+            max_attributes: 128,
+            max_events: 128,
+            max_links: 128,
+            max_attributes_per_event: 128,
+            max_attributes_per_link: 128,
+            max_attribute_value_length: None,
+        }
+    }
+}
+
+impl SpanLimits {
+    pub(crate) fn truncate(&self, value: Value) -> Value {
+        let max = match self.max_attribute_value_length {
+            Some(x) => x,
+            None => return value,
+        };
+        let truncate = |mut s: String| {
+            if s.len() > max {
+                let mut end = max;
+                while !s.is_char_boundary(end) {
+                    end -= 1;
+                }
+                s.truncate(end);
+            }
+            s
+        };
+        match value {
+            Value::String(s) => Value::String(truncate(s)),
+            Value::StringArray(xs) => Value::StringArray(xs.into_iter().map(truncate).collect()),
+            v => v,
+        }
+    }
+
+    /// Keeps at most `max` attributes, returning them with the number dropped.
+    pub(crate) fn limit_attributes(
+        &self,
+        attributes: HashMap<String, Value>,
+        max: usize,
+    ) -> (HashMap<String, Value>, u32) {
+        let dropped = attributes.len().saturating_sub(max) as u32;
+        let kept = attributes
+            .into_iter()
+            .take(max)
+            .map(|(k, v)| (k, self.truncate(v)))
+            .collect();
+        (kept, dropped)
+    }
+}
+
+#[test]
+fn span_limits_truncate() {
+    let limits = SpanLimits {
+        max_attribute_value_length: Some(4),
+        ..SpanLimits::default()
+    };
+    assert_eq!(
+        limits.truncate(Value::String("abcdef".to_owned())),
+        Value::String("abcd".to_owned())
+    );
+    assert_eq!(
+        limits.truncate(Value::String("aéé".to_owned())),
+        Value::String("aé".to_owned())
+    );
+    assert_eq!(
+        limits.truncate(Value::from(vec!["abcdef", "ab"])),
+        Value::from(vec!["abcd", "ab"])
+    );
+    assert_eq!(
+        limits.truncate(Value::Int64(123_456)),
+        Value::Int64(123_456)
+    );
+}
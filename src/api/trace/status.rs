@@ -1,4 +1,12 @@
-#[derive(Debug, PartialEq, Eq, Clone)]
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::api::trace::SpanKind;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CanonicalCode {
     Ok = 0,
     Cancelled = 1,
@@ -20,12 +28,20 @@ pub enum CanonicalCode {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Status {
     canonical_code: CanonicalCode,
     description: Option<String>,
 }
 
 impl Status {
+    pub fn new(canonical_code: CanonicalCode, description: Option<String>) -> Self {
+        Self {
+            canonical_code,
+            description,
+        }
+    }
+
     pub fn with_description(self, description: String) -> Self {
         Self {
             canonical_code: self.canonical_code,
@@ -33,6 +49,36 @@ impl Status {
         }
     }
 
+    /// The status of a failed operation: mapped from the kind of an `io::Error`, otherwise
+    /// `Status::unknown()`, described by the error's `Display` either way.
+    pub fn from_error(error: &(dyn Error + 'static)) -> Self {
+        match error.downcast_ref::<io::Error>() {
+            Some(x) => Self::from(x),
+            None => Self::unknown().with_description(error.to_string()),
+        }
+    }
+
+    /// [HTTP status mapping](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/data-http.md#status)
+    ///
+    /// A 4xx answer is the caller's fault, so it only marks `CLIENT` spans as failed.
+    pub fn from_http_status(status_code: u16, kind: SpanKind) -> Self {
+        let code = match status_code {
+            100..=399 => CanonicalCode::Ok,
+            400..=499 if kind == SpanKind::SERVER => CanonicalCode::Ok,
+            401 => CanonicalCode::Unauthenticated,
+            403 => CanonicalCode::PermissionDenied,
+            404 => CanonicalCode::NotFound,
+            429 => CanonicalCode::ResourceExhausted,
+            400..=499 => CanonicalCode::Argument,
+            501 => CanonicalCode::Unimplemented,
+            503 => CanonicalCode::Unavailable,
+            504 => CanonicalCode::DeadlineExceeded,
+            500..=599 => CanonicalCode::Internal,
+            _ => CanonicalCode::Unknown,
+        };
+        code.into()
+    }
+
     pub fn ok() -> Self {
         Self {
             canonical_code: CanonicalCode::Ok,
@@ -136,7 +182,158 @@ impl Status {
         }
     }
 
+    pub fn canonical_code(&self) -> CanonicalCode {
+        self.canonical_code
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
     pub fn is_ok(&self) -> bool {
         self.canonical_code == CanonicalCode::Ok
     }
 }
+
+impl From<CanonicalCode> for Status {
+    fn from(canonical_code: CanonicalCode) -> Self {
+        Self::new(canonical_code, None)
+    }
+}
+
+impl From<CanonicalCode> for i32 {
+    fn from(canonical_code: CanonicalCode) -> Self {
+        canonical_code as i32
+    }
+}
+
+impl TryFrom<i32> for CanonicalCode {
+    type Error = ();
+
+    fn try_from(code: i32) -> Result<Self, Self::Error> {
+        Ok(match code {
+            0 => CanonicalCode::Ok,
+            1 => CanonicalCode::Cancelled,
+            2 => CanonicalCode::Unknown,
+            3 => CanonicalCode::Argument,
+            4 => CanonicalCode::DeadlineExceeded,
+            5 => CanonicalCode::NotFound,
+            6 => CanonicalCode::AlreadyExists,
+            7 => CanonicalCode::PermissionDenied,
+            8 => CanonicalCode::ResourceExhausted,
+            9 => CanonicalCode::FailedPrecondition,
+            10 => CanonicalCode::Aborted,
+            11 => CanonicalCode::OutOfRange,
+            12 => CanonicalCode::Unimplemented,
+            13 => CanonicalCode::Internal,
+            14 => CanonicalCode::Unavailable,
+            15 => CanonicalCode::DataLoss,
+            16 => CanonicalCode::Unauthenticated,
+            _ => return Err(()),
+        })
+    }
+}
+
+impl TryFrom<i32> for Status {
+    type Error = ();
+
+    fn try_from(code: i32) -> Result<Self, Self::Error> {
+        CanonicalCode::try_from(code).map(Status::from)
+    }
+}
+
+impl From<io::ErrorKind> for CanonicalCode {
+    fn from(kind: io::ErrorKind) -> Self {
+        use io::ErrorKind::*;
+        match kind {
+            NotFound => CanonicalCode::NotFound,
+            PermissionDenied => CanonicalCode::PermissionDenied,
+            AlreadyExists | AddrInUse => CanonicalCode::AlreadyExists,
+            ConnectionRefused | ConnectionReset | ConnectionAborted | NotConnected
+            | AddrNotAvailable | BrokenPipe | WouldBlock => CanonicalCode::Unavailable,
+            InvalidInput | InvalidData => CanonicalCode::Argument,
+            TimedOut => CanonicalCode::DeadlineExceeded,
+            Interrupted => CanonicalCode::Cancelled,
+            UnexpectedEof => CanonicalCode::OutOfRange,
+            Unsupported => CanonicalCode::Unimplemented,
+            OutOfMemory => CanonicalCode::ResourceExhausted,
+            WriteZero => CanonicalCode::DataLoss,
+            _ => CanonicalCode::Unknown,
+        }
+    }
+}
+
+impl From<&io::Error> for Status {
+    fn from(error: &io::Error) -> Self {
+        Self::new(error.kind().into(), Some(error.to_string()))
+    }
+}
+
+impl fmt::Display for CanonicalCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// `NotFound` or `NotFound: <description>`.
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.description {
+            Some(d) => write!(f, "{}: {}", self.canonical_code, d),
+            None => write!(f, "{}", self.canonical_code),
+        }
+    }
+}
+
+#[test]
+fn status_from_http_status() {
+    assert!(Status::from_http_status(200, SpanKind::SERVER).is_ok());
+    assert!(Status::from_http_status(302, SpanKind::CLIENT).is_ok());
+    assert!(Status::from_http_status(404, SpanKind::SERVER).is_ok());
+    assert_eq!(
+        Status::from_http_status(404, SpanKind::CLIENT),
+        Status::not_found()
+    );
+    assert_eq!(
+        Status::from_http_status(418, SpanKind::CLIENT),
+        Status::argument()
+    );
+    assert_eq!(
+        Status::from_http_status(503, SpanKind::SERVER),
+        Status::unavailable()
+    );
+    assert_eq!(
+        Status::from_http_status(599, SpanKind::CLIENT),
+        Status::internal()
+    );
+    assert_eq!(
+        Status::from_http_status(600, SpanKind::CLIENT),
+        Status::unknown()
+    );
+}
+
+#[test]
+fn status_code_round_trip() {
+    for code in 0..=16 {
+        let c = CanonicalCode::try_from(code).unwrap();
+        assert_eq!(i32::from(c), code);
+        assert_eq!(Status::try_from(code).unwrap().canonical_code(), c);
+    }
+    assert!(CanonicalCode::try_from(17).is_err());
+    assert!(CanonicalCode::try_from(-1).is_err());
+}
+
+#[test]
+fn status_from_io_error() {
+    let e = io::Error::new(io::ErrorKind::TimedOut, "no answer");
+    let s = Status::from(&e);
+    assert_eq!(s.canonical_code(), CanonicalCode::DeadlineExceeded);
+    assert_eq!(s.to_string(), "DeadlineExceeded: no answer");
+    assert_eq!(Status::not_found().to_string(), "NotFound");
+    assert_eq!(Status::from_error(&e), s);
+    let other = "x".parse::<u8>().unwrap_err();
+    assert_eq!(
+        Status::from_error(&other),
+        Status::unknown().with_description(other.to_string())
+    );
+}
@@ -1,5 +1,6 @@
 use crate::api::trace::span_context::{SpanId, TraceId, TraceOption, TraceState};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraceContext {
     pub trace_id: TraceId,
     pub span_id: SpanId,
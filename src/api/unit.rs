@@ -1,15 +1,223 @@
+//! Units of measure as [UCUM](https://ucum.org/ucum) case-sensitive symbols.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Unit {
+    /// `1`
     Dimensionless,
-    Bytes,
+    /// `%`
+    Percent,
+    /// `{requests}`: a dimensionless count of what the annotation names, without braces.
+    Annotation(String),
+    /// `ns`
+    Nanoseconds,
+    /// `us`
+    Microseconds,
+    /// `ms`
     Milliseconds,
+    /// `s`
+    Seconds,
+    /// `min`
+    Minutes,
+    /// `h`
+    Hours,
+    /// `bit`
+    Bits,
+    /// `By`
+    Bytes,
+    /// `kBy`
+    Kilobytes,
+    /// `MBy`
+    Megabytes,
+    /// `GBy`
+    Gigabytes,
+    /// `KiBy`
+    Kibibytes,
+    /// `MiBy`
+    Mebibytes,
+    /// `GiBy`
+    Gibibytes,
+    /// `<unit>/s`, e.g. `By/s` or `{requests}/s`. The inner unit is never itself a rate.
+    PerSecond(Box<Unit>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Dimensionless,
+    Time,
+    Information,
 }
 
 impl Unit {
-    fn to_str(&self) -> &str {
-        match self {
+    pub fn as_str(&self) -> Cow<'_, str> {
+        let s = match self {
             Unit::Dimensionless => "1",
-            Unit::Bytes => "By",
+            Unit::Percent => "%",
+            Unit::Annotation(x) => return Cow::Owned(format!("{{{}}}", x)),
+            Unit::Nanoseconds => "ns",
+            Unit::Microseconds => "us",
             Unit::Milliseconds => "ms",
+            Unit::Seconds => "s",
+            Unit::Minutes => "min",
+            Unit::Hours => "h",
+            Unit::Bits => "bit",
+            Unit::Bytes => "By",
+            Unit::Kilobytes => "kBy",
+            Unit::Megabytes => "MBy",
+            Unit::Gigabytes => "GBy",
+            Unit::Kibibytes => "KiBy",
+            Unit::Mebibytes => "MiBy",
+            Unit::Gibibytes => "GiBy",
+            Unit::PerSecond(x) => return Cow::Owned(format!("{}/s", x.as_str())),
+        };
+        Cow::Borrowed(s)
+    }
+
+    /// Dimension, whether it is per second, and the size as `(mul, div)` of the base unit.
+    fn scale(&self) -> (Dimension, bool, f64, f64) {
+        let (dimension, mul, div) = match self {
+            Unit::Dimensionless | Unit::Annotation(_) => (Dimension::Dimensionless, 1.0, 1.0),
+            Unit::Percent => (Dimension::Dimensionless, 1.0, 100.0),
+            Unit::Nanoseconds => (Dimension::Time, 1.0, 1e9),
+            Unit::Microseconds => (Dimension::Time, 1.0, 1e6),
+            Unit::Milliseconds => (Dimension::Time, 1.0, 1e3),
+            Unit::Seconds => (Dimension::Time, 1.0, 1.0),
+            Unit::Minutes => (Dimension::Time, 60.0, 1.0),
+            Unit::Hours => (Dimension::Time, 3600.0, 1.0),
+            Unit::Bits => (Dimension::Information, 1.0, 8.0),
+            Unit::Bytes => (Dimension::Information, 1.0, 1.0),
+            Unit::Kilobytes => (Dimension::Information, 1e3, 1.0),
+            Unit::Megabytes => (Dimension::Information, 1e6, 1.0),
+            Unit::Gigabytes => (Dimension::Information, 1e9, 1.0),
+            Unit::Kibibytes => (Dimension::Information, 1024.0, 1.0),
+            Unit::Mebibytes => (Dimension::Information, 1024.0 * 1024.0, 1.0),
+            Unit::Gibibytes => (Dimension::Information, 1024.0 * 1024.0 * 1024.0, 1.0),
+            Unit::PerSecond(x) => {
+                let (dimension, _, mul, div) = x.scale();
+                return (dimension, true, mul, div);
+            }
+        };
+        (dimension, false, mul, div)
+    }
+
+    /// Unit of the same dimension that exporters normalize to: `1`, `s`, `By`, or their rate
+    /// per second. Annotations are kept, and `%` becomes `1`.
+    pub fn base(&self) -> Unit {
+        match self {
+            Unit::Annotation(_) => self.clone(),
+            Unit::PerSecond(x) => Unit::PerSecond(Box::new(x.base())),
+            _ => match self.scale().0 {
+                Dimension::Dimensionless => Unit::Dimensionless,
+                Dimension::Time => Unit::Seconds,
+                Dimension::Information => Unit::Bytes,
+            },
         }
     }
+
+    pub fn is_compatible(&self, other: &Unit) -> bool {
+        let (a, a_rate, _, _) = self.scale();
+        let (b, b_rate, _, _) = other.scale();
+        a == b && a_rate == b_rate
+    }
+
+    /// `value` in `self` expressed in `to`, or `None` if the units are not compatible.
+    pub fn convert(&self, value: f64, to: &Unit) -> Option<f64> {
+        if !self.is_compatible(to) {
+            return None;
+        }
+        let (_, _, from_mul, from_div) = self.scale();
+        let (_, _, to_mul, to_div) = to.scale();
+        Some(value * from_mul * to_div / (from_div * to_mul))
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.as_str())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = ();
+
+    /// Accepts the symbols listed on each variant; the empty string is `1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(x) = s.strip_prefix('{').and_then(|x| x.strip_suffix('}')) {
+            return if !x.is_empty()
+                && x.chars()
+                    .all(|c| c > ' ' && c <= '~' && c != '{' && c != '}')
+            {
+                Ok(Unit::Annotation(x.to_owned()))
+            } else {
+                Err(())
+            };
+        }
+        if let Some(x) = s.strip_suffix("/s") {
+            return match x.parse()? {
+                Unit::PerSecond(_) => Err(()),
+                x => Ok(Unit::PerSecond(Box::new(x))),
+            };
+        }
+        Ok(match s {
+            "" | "1" => Unit::Dimensionless,
+            "%" => Unit::Percent,
+            "ns" => Unit::Nanoseconds,
+            "us" => Unit::Microseconds,
+            "ms" => Unit::Milliseconds,
+            "s" => Unit::Seconds,
+            "min" => Unit::Minutes,
+            "h" => Unit::Hours,
+            "bit" => Unit::Bits,
+            "By" => Unit::Bytes,
+            "kBy" => Unit::Kilobytes,
+            "MBy" => Unit::Megabytes,
+            "GBy" => Unit::Gigabytes,
+            "KiBy" => Unit::Kibibytes,
+            "MiBy" => Unit::Mebibytes,
+            "GiBy" => Unit::Gibibytes,
+            _ => return Err(()),
+        })
+    }
+}
+
+#[test]
+fn unit_parse_and_convert() {
+    for s in &[
+        "1",
+        "%",
+        "{requests}",
+        "us",
+        "min",
+        "KiBy",
+        "bit/s",
+        "{packets}/s",
+        "{a/s}",
+    ] {
+        let unit: Unit = s.parse().unwrap();
+        assert_eq!(unit.to_string(), *s);
+    }
+    assert_eq!("".parse(), Ok(Unit::Dimensionless));
+    assert!("{}".parse::<Unit>().is_err());
+    assert!("s/s/s".parse::<Unit>().is_err());
+    assert!("furlong".parse::<Unit>().is_err());
+
+    assert_eq!(
+        Unit::Milliseconds.convert(1500.0, &Unit::Seconds),
+        Some(1.5)
+    );
+    assert_eq!(
+        Unit::Milliseconds.convert(555.0, &Unit::Seconds),
+        Some(0.555)
+    );
+    assert_eq!(Unit::Mebibytes.convert(1.0, &Unit::Kibibytes), Some(1024.0));
+    assert_eq!(Unit::Bits.convert(16.0, &Unit::Bytes), Some(2.0));
+    assert_eq!(Unit::Percent.convert(50.0, &Unit::Dimensionless), Some(0.5));
+    let kbps = Unit::PerSecond(Box::new(Unit::Kilobytes));
+    assert_eq!(kbps.convert(2.0, &kbps.base()), Some(2000.0));
+    assert_eq!(kbps.base().to_string(), "By/s");
+    assert_eq!(Unit::Seconds.convert(1.0, &Unit::Bytes), None);
+    assert_eq!(kbps.convert(1.0, &Unit::Bytes), None);
 }
@@ -0,0 +1,134 @@
+//! Prints recorded traces and optionally re-exports them.
+//!
+//! ```text
+//! ot-replay <FILE> [--rotated] [--exporter <NAMES>] [--batch-size <N>]
+//! ```
+//!
+//! Spans are re-exported through the exporters selected by `OTEL_TRACES_EXPORTER`, or by
+//! `--exporter` which takes the same comma-separated names, and configured by the usual
+//! `OTEL_EXPORTER_*` variables.
+
+use std::env;
+use std::process;
+
+use ot_rs::exporter::replay::{self, span_duration_as_micros, Trace};
+use ot_rs::sdk::config::EnvConfig;
+use ot_rs::sdk::trace::export::{ResultCode, SpanExporter};
+
+const USAGE: &str = "usage: ot-replay <FILE> [--rotated] [--exporter <NAMES>] [--batch-size <N>]";
+
+struct Args {
+    path: String,
+    rotated: bool,
+    exporter: Option<String>,
+    batch_size: usize,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut path = None;
+    let mut rotated = false;
+    let mut exporter = None;
+    let mut batch_size = 512;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rotated" => rotated = true,
+            "--exporter" => exporter = Some(args.next().ok_or("--exporter needs names")?),
+            "--batch-size" => {
+                batch_size = args
+                    .next()
+                    .and_then(|x| x.parse().ok())
+                    .ok_or("--batch-size needs a number")?
+            }
+            x if x.starts_with("--") => return Err(format!("unknown option {}", x)),
+            _ => path = Some(arg),
+        }
+    }
+
+    Ok(Args {
+        path: path.ok_or("missing FILE")?,
+        rotated,
+        exporter,
+        batch_size,
+    })
+}
+
+fn format_micros(x: u128) -> String {
+    format!("{}.{:03}ms", x / 1000, x % 1000)
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|e| {
+        eprintln!("{}\n{}", e, USAGE);
+        process::exit(2);
+    });
+
+    let read = if args.rotated {
+        replay::read_rotated(&args.path)
+    } else {
+        replay::read_spans(&args.path)
+    };
+    let spans = read.unwrap_or_else(|e| {
+        eprintln!("{}: {}", args.path, e);
+        process::exit(1);
+    });
+
+    let traces = Trace::assemble(spans.clone());
+    for t in traces.iter() {
+        println!(
+            "trace {} ({} spans, {})",
+            t.trace_id().to_base16(),
+            t.spans().len(),
+            format_micros(t.duration_as_micros())
+        );
+        println!("  critical path:");
+        for s in t.critical_path() {
+            println!(
+                "    {} {}",
+                s.name(),
+                format_micros(span_duration_as_micros(s))
+            );
+        }
+        println!("  spans:");
+        for s in t.spans() {
+            println!(
+                "    {} {} {}",
+                s.span_id().to_base16(),
+                s.name(),
+                format_micros(span_duration_as_micros(s))
+            );
+        }
+    }
+
+    let config = EnvConfig::from_vars(|x| match (x, &args.exporter) {
+        ("OTEL_TRACES_EXPORTER", Some(names)) => Some(names.clone()),
+        _ => env::var(x).ok(),
+    });
+    for warning in config.warnings() {
+        eprintln!("warning: {}", warning);
+    }
+    let mut exporters: Vec<Box<dyn SpanExporter + Send>> = Vec::new();
+    for exporter in config.exporters() {
+        match exporter.build() {
+            Ok(x) => exporters.push(x),
+            Err(e) => {
+                eprintln!("{:?}: {}", exporter, e);
+                process::exit(2);
+            }
+        }
+    }
+
+    let mut failed = false;
+    for mut exporter in exporters {
+        let result = replay::replay(&spans, exporter.as_mut(), args.batch_size);
+        if result != ResultCode::Success {
+            eprintln!("export failed: {:?}", result);
+            failed = true;
+        }
+        exporter.shutdown();
+    }
+    if failed {
+        process::exit(1);
+    }
+}
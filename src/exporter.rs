@@ -0,0 +1,62 @@
+pub mod file;
+pub(crate) mod http;
+pub mod in_memory;
+pub(crate) mod json;
+pub mod prometheus;
+pub mod replay;
+pub mod statsd;
+pub mod stdout;
+pub mod zipkin;
+
+/// Builds a finished span for exporter tests; times are micros since the epoch.
+#[cfg(test)]
+pub(crate) fn test_span_data(
+    resource: &crate::api::resources::Resource,
+    name: &str,
+    span_id: u64,
+    parent_span_id: Option<u64>,
+    start_micros: u64,
+    end_micros: u64,
+) -> crate::api::trace::span_data::SpanData {
+    use crate::api::trace::clock::SystemClock;
+    use crate::api::trace::in_memory::InMemorySpan;
+    use crate::api::trace::span_context::{SpanContext, SpanId, TraceId, TraceOption, TraceState};
+    use crate::api::trace::span_data::SpanData;
+    use crate::api::trace::span_limits::SpanLimits;
+    use crate::api::trace::status::Status;
+    use crate::api::trace::{SpanKind, Timestamp};
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+    use std::num::{NonZeroU128, NonZeroU64};
+    use std::sync::Arc;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let t = TraceId::new(NonZeroU128::new(42).unwrap());
+    let p = parent_span_id.and_then(NonZeroU64::new).map(SpanId::new);
+    let span = InMemorySpan {
+        context: SpanContext::new(
+            &t,
+            SpanId::new(NonZeroU64::new(span_id).unwrap()),
+            TraceOption::MASK_SAMPLE,
+            TraceState::empty(),
+        ),
+        resource,
+        name: name.to_owned(),
+        kind: SpanKind::INTERNAL,
+        start_time: Timestamp(UNIX_EPOCH + Duration::from_micros(start_micros)),
+        clock: Arc::new(SystemClock::new()),
+        start_monotonic: None,
+        finish_time: Some(Timestamp(UNIX_EPOCH + Duration::from_micros(end_micros))),
+        attributes: HashMap::new(),
+        parent_span_id: p.as_ref(),
+        links: Vec::new(),
+        events: Vec::new(),
+        status: Status::ok(),
+        limits: SpanLimits::default(),
+        dropped_attributes_count: 0,
+        dropped_events_count: 0,
+        dropped_links_count: 0,
+        on_end: None,
+    };
+    SpanData::try_from(span).unwrap()
+}
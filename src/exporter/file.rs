@@ -0,0 +1,266 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::api::trace::span_data::SpanData;
+use crate::exporter::json;
+use crate::sdk::trace::export::{ResultCode, SpanExporter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Never,
+    /// Starts a new file before a batch would grow the current one past this many bytes.
+    Size(u64),
+    /// Starts a new file once the current one has been open this long, unless it is empty.
+    Interval(Duration),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// Leaves flushing to the OS, except on rotation and shutdown.
+    Never,
+    EveryBatch,
+    /// Syncs after a batch if at least this long has passed since the last sync.
+    Interval(Duration),
+}
+
+/// Appends spans as JSON-lines to a file, rotating it into `<path>.1`, `<path>.2`, ...
+///
+/// The newest rotated file is `<path>.1`; at most `max_generations` of them are kept.
+pub struct FileExporter {
+    path: PathBuf,
+    rotation: Rotation,
+    max_generations: usize,
+    fsync: FsyncPolicy,
+    file: Option<File>,
+    written: u64,
+    opened_at: Instant,
+    synced_at: Instant,
+    is_shutdown: bool,
+}
+
+impl FileExporter {
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = open_append(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            rotation: Rotation::Never,
+            max_generations: 5,
+            fsync: FsyncPolicy::Never,
+            file: Some(file),
+            written,
+            opened_at: Instant::now(),
+            synced_at: Instant::now(),
+            is_shutdown: false,
+        })
+    }
+
+    pub fn with_rotation(self, rotation: Rotation) -> Self {
+        Self { rotation, ..self }
+    }
+
+    pub fn with_max_generations(self, max_generations: usize) -> Self {
+        Self {
+            max_generations,
+            ..self
+        }
+    }
+
+    pub fn with_fsync(self, fsync: FsyncPolicy) -> Self {
+        Self { fsync, ..self }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Path of the `n`th rotated file; generation `0` is the active file.
+    pub fn generation_path(&self, n: usize) -> PathBuf {
+        generation_path(&self.path, n)
+    }
+
+    fn should_rotate(&self, incoming: u64) -> bool {
+        match self.rotation {
+            Rotation::Never => false,
+            Rotation::Size(max) => self.written > 0 && self.written + incoming > max,
+            Rotation::Interval(x) => self.written > 0 && self.opened_at.elapsed() >= x,
+        }
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if let Some(file) = self.file.take() {
+            file.sync_all()?;
+        }
+
+        if self.max_generations == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let _ = fs::remove_file(generation_path(&self.path, self.max_generations));
+            for n in (1..self.max_generations).rev() {
+                let from = generation_path(&self.path, n);
+                if from.exists() {
+                    fs::rename(&from, generation_path(&self.path, n + 1))?;
+                }
+            }
+            fs::rename(&self.path, generation_path(&self.path, 1))?;
+        }
+
+        self.file = Some(open_append(&self.path)?);
+        self.written = 0;
+        self.opened_at = Instant::now();
+        Ok(())
+    }
+
+    fn write_batch(&mut self, batch: &[SpanData]) -> io::Result<()> {
+        let mut buf = Vec::new();
+        for span in batch {
+            serde_json::to_writer(&mut buf, &json::encode_span(span))?;
+            buf.push(b'\n');
+        }
+
+        if self.should_rotate(buf.len() as u64) {
+            self.rotate()?;
+        }
+        let file = match self.file.as_mut() {
+            Some(x) => x,
+            None => self.file.get_or_insert(open_append(&self.path)?),
+        };
+        file.write_all(&buf)?;
+        self.written += buf.len() as u64;
+
+        let sync = match self.fsync {
+            FsyncPolicy::Never => false,
+            FsyncPolicy::EveryBatch => true,
+            FsyncPolicy::Interval(x) => self.synced_at.elapsed() >= x,
+        };
+        if sync {
+            file.sync_data()?;
+            self.synced_at = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+impl SpanExporter for FileExporter {
+    fn export(&mut self, batch: &[SpanData]) -> ResultCode {
+        if self.is_shutdown {
+            return ResultCode::FailedNotRetryable;
+        }
+        match self.write_batch(batch) {
+            Ok(_) => ResultCode::Success,
+            Err(_) => ResultCode::FailedRetryable,
+        }
+    }
+
+    fn shutdown(&mut self) {
+        self.is_shutdown = true;
+        if let Some(file) = self.file.take() {
+            let _ = file.sync_all();
+        }
+    }
+}
+
+pub(crate) fn generation_path(path: &Path, n: usize) -> PathBuf {
+    if n == 0 {
+        return path.to_path_buf();
+    }
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ot-rs-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn file_append() {
+    use crate::api::resources::Resource;
+    use crate::exporter::test_span_data;
+
+    let dir = test_dir("file-append");
+    let path = dir.join("spans.jsonl");
+    let r = Resource::default();
+
+    let mut exporter = FileExporter::new(&path)
+        .unwrap()
+        .with_fsync(FsyncPolicy::EveryBatch);
+    let batch = [test_span_data(&r, "a", 1, None, 1, 2)];
+    assert_eq!(exporter.export(&batch), ResultCode::Success);
+    exporter.shutdown();
+
+    let mut exporter = FileExporter::new(&path).unwrap();
+    assert_eq!(exporter.export(&batch), ResultCode::Success);
+    exporter.shutdown();
+    assert_eq!(exporter.export(&batch), ResultCode::FailedNotRetryable);
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content.lines().count(), 2);
+    assert!(!exporter.generation_path(1).exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn file_rotate_by_size() {
+    use crate::api::resources::Resource;
+    use crate::exporter::test_span_data;
+
+    let dir = test_dir("file-rotate");
+    let path = dir.join("spans.jsonl");
+    let r = Resource::default();
+    let batch = [test_span_data(&r, "a", 1, None, 1, 2)];
+    let line = json::encode_span(&batch[0]).to_string().len() as u64 + 1;
+
+    let mut exporter = FileExporter::new(&path)
+        .unwrap()
+        .with_rotation(Rotation::Size(line * 2))
+        .with_max_generations(2);
+    for _ in 0..7 {
+        assert_eq!(exporter.export(&batch), ResultCode::Success);
+    }
+    exporter.shutdown();
+
+    let lines = |n| {
+        fs::read_to_string(generation_path(&path, n))
+            .unwrap()
+            .lines()
+            .count()
+    };
+    assert_eq!(lines(0), 1);
+    assert_eq!(lines(1), 2);
+    assert_eq!(lines(2), 2);
+    assert!(!generation_path(&path, 3).exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn file_rotate_by_interval_skips_empty_files() {
+    use crate::api::resources::Resource;
+    use crate::exporter::test_span_data;
+
+    let dir = test_dir("file-rotate-interval");
+    let path = dir.join("spans.jsonl");
+    let r = Resource::default();
+    let batch = [test_span_data(&r, "a", 1, None, 1, 2)];
+
+    let mut exporter = FileExporter::new(&path)
+        .unwrap()
+        .with_rotation(Rotation::Interval(Duration::from_secs(0)));
+    assert_eq!(exporter.export(&batch), ResultCode::Success);
+    assert!(!generation_path(&path, 1).exists());
+    assert_eq!(exporter.export(&batch), ResultCode::Success);
+    assert!(generation_path(&path, 1).exists());
+    exporter.shutdown();
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,126 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// `http://host[:port]/path` split into its parts; an IPv6 host is written in brackets,
+/// e.g. `http://[::1]:9411/`, and kept without them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Url {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) path: String,
+}
+
+impl Url {
+    pub(crate) fn parse(url: &str) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidInput, url.to_owned());
+        let rest = url.strip_prefix("http://").ok_or_else(invalid)?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(x) => {
+                let i = x.find(']').ok_or_else(invalid)?;
+                (&x[..i], &x[i + 1..])
+            }
+            None => match authority.rfind(':') {
+                Some(i) => (&authority[..i], &authority[i..]),
+                None => (authority, ""),
+            },
+        };
+        let port = match port {
+            "" => 80,
+            x => x
+                .strip_prefix(':')
+                .and_then(|x| x.parse().ok())
+                .ok_or_else(invalid)?,
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            host: host.to_owned(),
+            port,
+            path: path.to_owned(),
+        })
+    }
+
+    /// `host:port`, with an IPv6 host in brackets.
+    pub(crate) fn authority(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// Sends `body` with a blocking HTTP/1.1 POST and returns the response status code.
+pub(crate) fn post(
+    url: &Url,
+    content_type: &str,
+    body: &[u8],
+    timeout: Duration,
+) -> io::Result<u16> {
+    let mut stream = connect(url, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let mut request = Vec::with_capacity(body.len() + 128);
+    write!(
+        request,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        url.path,
+        url.authority(),
+        content_type,
+        body.len()
+    )?;
+    request.extend_from_slice(body);
+    stream.write_all(&request)?;
+    stream.flush()?;
+
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line)?;
+    status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|x| x.parse().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, status_line.clone()))
+}
+
+/// Tries each resolved address in turn, waiting at most `timeout` for each.
+fn connect(url: &Url, timeout: Duration) -> io::Result<TcpStream> {
+    let mut error = None;
+    for address in (url.host.as_str(), url.port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(x) => return Ok(x),
+            Err(e) => error = Some(e),
+        }
+    }
+    Err(error.unwrap_or_else(|| io::Error::new(io::ErrorKind::NotFound, url.host.clone())))
+}
+
+#[test]
+fn url_parse() {
+    let a = Url::parse("http://localhost:9411/api/v2/spans").unwrap();
+    assert_eq!(a.host, "localhost");
+    assert_eq!(a.port, 9411);
+    assert_eq!(a.path, "/api/v2/spans");
+
+    let b = Url::parse("http://zipkin").unwrap();
+    assert_eq!(b.port, 80);
+    assert_eq!(b.path, "/");
+
+    assert!(Url::parse("https://zipkin").is_err());
+    assert!(Url::parse("http://:80/").is_err());
+
+    let c = Url::parse("http://[::1]:9411/api/v2/spans").unwrap();
+    assert_eq!(c.host, "::1");
+    assert_eq!(c.port, 9411);
+    assert_eq!(c.authority(), "[::1]:9411");
+    assert_eq!(Url::parse("http://[::1]/").unwrap().port, 80);
+    assert!(Url::parse("http://[::1/").is_err());
+    assert!(Url::parse("http://[::1]x/").is_err());
+}
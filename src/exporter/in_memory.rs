@@ -0,0 +1,147 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::api::trace::key::Value;
+use crate::api::trace::span_context::{SpanId, TraceId};
+use crate::api::trace::span_data::SpanData;
+use crate::sdk::trace::export::{ResultCode, SpanExporter};
+
+/// Collects every exported span so tests can inspect them.
+///
+/// Clones share the same buffer, so keep one clone in the test and hand the other to the SDK.
+#[derive(Clone, Default)]
+pub struct InMemorySpanExporter {
+    spans: Arc<Mutex<Vec<SpanData>>>,
+    is_shutdown: Arc<Mutex<bool>>,
+}
+
+/// Expected shape of a trace: a span name and the names of its children in start order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanTree {
+    name: String,
+    children: Vec<SpanTree>,
+}
+
+impl SpanTree {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn with_child(mut self, child: SpanTree) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+impl InMemorySpanExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<SpanData>> {
+        self.spans.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn finished_spans(&self) -> Vec<SpanData> {
+        self.lock().clone()
+    }
+
+    pub fn reset(&self) {
+        self.lock().clear();
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        *self.is_shutdown.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Vec<SpanData> {
+        self.filter(|x| x.name() == name)
+    }
+
+    pub fn find_by_trace_id(&self, trace_id: &TraceId) -> Vec<SpanData> {
+        self.filter(|x| x.trace_id() == trace_id)
+    }
+
+    pub fn children_of(&self, span_id: &SpanId) -> Vec<SpanData> {
+        self.filter(|x| x.parent_span_id() == Some(span_id))
+    }
+
+    fn filter<P>(&self, predicate: P) -> Vec<SpanData>
+    where
+        P: Fn(&SpanData) -> bool,
+    {
+        self.lock()
+            .iter()
+            .filter(|x| predicate(x))
+            .cloned()
+            .collect()
+    }
+
+    fn tree_of(&self, span: &SpanData) -> SpanTree {
+        let mut children = self.filter(|x| {
+            x.trace_id() == span.trace_id() && x.parent_span_id() == Some(span.span_id())
+        });
+        children.sort_by_key(|x| x.start_time().as_nanos());
+        SpanTree {
+            name: span.name().to_owned(),
+            children: children.iter().map(|x| self.tree_of(x)).collect(),
+        }
+    }
+
+    /// Panics unless some exported root span has exactly the `expected` shape.
+    pub fn assert_tree(&self, expected: &SpanTree) {
+        let actual: Vec<SpanTree> = self
+            .filter(|x| x.parent_span_id().is_none() && x.name() == expected.name)
+            .iter()
+            .map(|x| self.tree_of(x))
+            .collect();
+        assert!(
+            actual.contains(expected),
+            "no trace matches {:?}, found {:?}",
+            expected,
+            actual
+        );
+    }
+
+    /// Panics unless some span named `span_name` has the attribute `key`.
+    pub fn assert_attribute(&self, span_name: &str, key: &str) {
+        assert!(
+            self.find_by_name(span_name)
+                .iter()
+                .any(|x| x.attributes().any(|(k, _)| k == key)),
+            "no span {:?} has attribute {:?}",
+            span_name,
+            key
+        );
+    }
+
+    /// Panics unless some span named `span_name` has the attribute `key` set to `value`.
+    pub fn assert_attribute_eq(&self, span_name: &str, key: &str, value: &Value) {
+        assert!(
+            self.find_by_name(span_name)
+                .iter()
+                .any(|x| x.attributes().any(|(k, v)| k == key && v == value)),
+            "no span {:?} has attribute {:?} = {:?}",
+            span_name,
+            key,
+            value
+        );
+    }
+}
+
+impl SpanExporter for InMemorySpanExporter {
+    fn export(&mut self, batch: &[SpanData]) -> ResultCode {
+        if self.is_shutdown() {
+            return ResultCode::FailedNotRetryable;
+        }
+        self.lock().extend_from_slice(batch);
+        ResultCode::Success
+    }
+
+    fn shutdown(&mut self) {
+        *self.is_shutdown.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        self.reset();
+    }
+}
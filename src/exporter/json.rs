@@ -0,0 +1,473 @@
+//! Lossless JSON representation of `SpanData`, one object per span.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde_json::{json, Map, Value as Json};
+
+use crate::api::context::{ToHttpText, TryFromHttpText};
+use crate::api::resources::{Resource, ResourceValue};
+use crate::api::trace::key::Value;
+use crate::api::trace::span_context::{SpanId, TraceId, TraceOption, TraceState};
+use crate::api::trace::span_data::{ImmutableLink, ImmutableSpanContext, SpanData};
+use crate::api::trace::status::{CanonicalCode, Status};
+use crate::api::trace::{Event, SpanKind, TimedEvent, Timestamp};
+
+pub(crate) fn encode_span(span: &SpanData) -> Json {
+    let mut o = Map::new();
+    o.insert("traceId".to_owned(), json!(span.trace_id().to_base16()));
+    o.insert("spanId".to_owned(), json!(span.span_id().to_base16()));
+    if let Some(p) = span.parent_span_id() {
+        o.insert("parentSpanId".to_owned(), json!(p.to_base16()));
+    }
+    o.insert(
+        "traceOption".to_owned(),
+        json!(span.trace_option().to_base16()),
+    );
+    if span.trace_state().has_entry() {
+        o.insert(
+            "traceState".to_owned(),
+            json!(span.trace_state().to_http_text()),
+        );
+    }
+    o.insert("name".to_owned(), json!(span.name()));
+    o.insert("kind".to_owned(), json!(encode_kind(span.kind())));
+    o.insert(
+        "startTimeUnixNano".to_owned(),
+        json!(span.start_time().as_nanos().unwrap_or(0) as u64),
+    );
+    o.insert(
+        "endTimeUnixNano".to_owned(),
+        json!(span.end_time().as_nanos().unwrap_or(0) as u64),
+    );
+    o.insert("resource".to_owned(), encode_resource(span.resource()));
+    if let Some(x) = span.resource().schema_url() {
+        o.insert("schemaUrl".to_owned(), json!(x));
+    }
+    o.insert(
+        "attributes".to_owned(),
+        encode_attributes(span.attributes()),
+    );
+    o.insert(
+        "events".to_owned(),
+        Json::Array(span.events().map(encode_event).collect()),
+    );
+    o.insert(
+        "links".to_owned(),
+        Json::Array(span.links.iter().map(encode_link).collect()),
+    );
+    let mut status = Map::new();
+    status.insert(
+        "code".to_owned(),
+        json!(i32::from(span.status().canonical_code())),
+    );
+    if let Some(d) = span.status().description() {
+        status.insert("description".to_owned(), json!(d));
+    }
+    o.insert("status".to_owned(), Json::Object(status));
+    insert_count(
+        &mut o,
+        "droppedAttributesCount",
+        span.dropped_attributes_count(),
+    );
+    insert_count(&mut o, "droppedEventsCount", span.dropped_events_count());
+    insert_count(&mut o, "droppedLinksCount", span.dropped_links_count());
+
+    Json::Object(o)
+}
+
+pub(crate) fn encode_kind(kind: SpanKind) -> &'static str {
+    match kind {
+        SpanKind::INTERNAL => "INTERNAL",
+        SpanKind::SERVER => "SERVER",
+        SpanKind::CLIENT => "CLIENT",
+        SpanKind::PRODUCER => "PRODUCER",
+        SpanKind::CONSUMER => "CONSUMER",
+    }
+}
+
+fn encode_resource(resource: &Resource) -> Json {
+    Json::Object(
+        resource
+            .labels()
+            .map(|(k, v)| (k.value().to_owned(), encode_resource_value(v)))
+            .collect(),
+    )
+}
+
+/// Resource values are untagged, so a non-finite double comes back as a string.
+fn encode_resource_value(value: &ResourceValue) -> Json {
+    match value {
+        ResourceValue::Bool(x) => json!(x),
+        ResourceValue::Int(x) => json!(x),
+        ResourceValue::Double(x) => encode_double(*x),
+        ResourceValue::String(x) => json!(x),
+    }
+}
+
+fn encode_attributes<'a>(attributes: impl Iterator<Item = (&'a String, &'a Value)>) -> Json {
+    Json::Object(
+        attributes
+            .map(|(k, v)| (k.to_owned(), encode_value(v)))
+            .collect(),
+    )
+}
+
+/// Non-finite doubles as the strings `NaN`, `Infinity` and `-Infinity`, which JSON numbers
+/// cannot represent.
+fn encode_double(x: f64) -> Json {
+    if x.is_nan() {
+        json!("NaN")
+    } else if x.is_infinite() {
+        json!(if x > 0.0 { "Infinity" } else { "-Infinity" })
+    } else {
+        json!(x)
+    }
+}
+
+/// Values are tagged with their type so that `Int64` and `UInt64` survive a round trip.
+fn encode_value(value: &Value) -> Json {
+    match value {
+        Value::Bool(x) => json!({ "boolValue": x }),
+        Value::Int64(x) => json!({ "intValue": x }),
+        Value::UInt64(x) => json!({ "uintValue": x }),
+        Value::Float64(x) => json!({ "doubleValue": encode_double(*x) }),
+        Value::String(x) => json!({ "stringValue": x }),
+        Value::Bytes(_) => json!({ "bytesValue": value.to_string() }),
+        Value::BoolArray(xs) => json!({ "boolArrayValue": xs }),
+        Value::Int64Array(xs) => json!({ "intArrayValue": xs }),
+        Value::UInt64Array(xs) => json!({ "uintArrayValue": xs }),
+        Value::Float64Array(xs) => json!({
+            "doubleArrayValue": xs.iter().map(|x| encode_double(*x)).collect::<Vec<_>>()
+        }),
+        Value::StringArray(xs) => json!({ "stringArrayValue": xs }),
+    }
+}
+
+/// Dropped counts are only written when something was dropped.
+fn insert_count(o: &mut Map<String, Json>, key: &str, count: u32) {
+    if count > 0 {
+        o.insert(key.to_owned(), json!(count));
+    }
+}
+
+fn encode_event(event: &TimedEvent) -> Json {
+    let mut o = Map::new();
+    o.insert(
+        "timeUnixNano".to_owned(),
+        json!(event.timestamp().as_nanos().unwrap_or(0) as u64),
+    );
+    o.insert("name".to_owned(), json!(event.name()));
+    o.insert(
+        "attributes".to_owned(),
+        encode_attributes(event.attributes()),
+    );
+    insert_count(
+        &mut o,
+        "droppedAttributesCount",
+        event.dropped_attributes_count(),
+    );
+    Json::Object(o)
+}
+
+fn encode_link(link: &ImmutableLink) -> Json {
+    let mut o = Map::new();
+    o.insert(
+        "traceId".to_owned(),
+        json!(link.span_context.trace_id.to_base16()),
+    );
+    o.insert(
+        "spanId".to_owned(),
+        json!(link.span_context.span_id.to_base16()),
+    );
+    o.insert(
+        "traceOption".to_owned(),
+        json!(link.span_context.trace_option.to_base16()),
+    );
+    if link.span_context.trace_state.has_entry() {
+        o.insert(
+            "traceState".to_owned(),
+            json!(link.span_context.trace_state.to_http_text()),
+        );
+    }
+    o.insert(
+        "attributes".to_owned(),
+        encode_attributes(link.attributes.iter()),
+    );
+    insert_count(
+        &mut o,
+        "droppedAttributesCount",
+        link.dropped_attributes_count,
+    );
+    Json::Object(o)
+}
+
+/// Inverse of `encode_span`; `None` if a required field is missing or malformed.
+pub(crate) fn decode_span(json: &Json) -> Option<SpanData> {
+    let o = json.as_object()?;
+    let trace_id = TraceId::try_from(o.get("traceId")?.as_str()?).ok()?;
+    let trace_state = match o.get("traceState") {
+        Some(x) => TraceState::try_from_http_text(x.as_str()?).ok()?,
+        None => TraceState::empty(),
+    };
+    let parent_span_id = match o.get("parentSpanId") {
+        Some(x) => Some(SpanId::try_from(x.as_str()?).ok()?),
+        None => None,
+    };
+    let mut resource = decode_resource(o.get("resource")?)?;
+    if let Some(x) = o.get("schemaUrl") {
+        resource = resource.with_schema_url(x.as_str()?);
+    }
+    let status = o.get("status")?;
+
+    Some(SpanData {
+        context: ImmutableSpanContext {
+            trace_id,
+            span_id: SpanId::try_from(o.get("spanId")?.as_str()?).ok()?,
+            trace_option: TraceOption::try_from_base16(o.get("traceOption")?.as_str()?)?,
+            trace_state,
+        },
+        resource,
+        parent_span_id,
+        name: o.get("name")?.as_str()?.to_owned(),
+        kind: decode_kind(o.get("kind")?.as_str()?)?,
+        start_time: decode_timestamp(o.get("startTimeUnixNano")?)?,
+        end_time: decode_timestamp(o.get("endTimeUnixNano")?)?,
+        attributes: decode_attributes(o.get("attributes")?)?,
+        events: o
+            .get("events")?
+            .as_array()?
+            .iter()
+            .map(decode_event)
+            .collect::<Option<_>>()?,
+        links: o
+            .get("links")?
+            .as_array()?
+            .iter()
+            .map(decode_link)
+            .collect::<Option<_>>()?,
+        status: Status::new(
+            CanonicalCode::try_from(i32::try_from(status.get("code")?.as_i64()?).ok()?).ok()?,
+            match status.get("description") {
+                Some(x) => Some(x.as_str()?.to_owned()),
+                None => None,
+            },
+        ),
+        dropped_attributes_count: decode_count(o.get("droppedAttributesCount"))?,
+        dropped_events_count: decode_count(o.get("droppedEventsCount"))?,
+        dropped_links_count: decode_count(o.get("droppedLinksCount"))?,
+    })
+}
+
+fn decode_count(json: Option<&Json>) -> Option<u32> {
+    match json {
+        Some(x) => u32::try_from(x.as_u64()?).ok(),
+        None => Some(0),
+    }
+}
+
+fn decode_kind(s: &str) -> Option<SpanKind> {
+    match s {
+        "INTERNAL" => Some(SpanKind::INTERNAL),
+        "SERVER" => Some(SpanKind::SERVER),
+        "CLIENT" => Some(SpanKind::CLIENT),
+        "PRODUCER" => Some(SpanKind::PRODUCER),
+        "CONSUMER" => Some(SpanKind::CONSUMER),
+        _ => None,
+    }
+}
+
+fn decode_timestamp(json: &Json) -> Option<Timestamp> {
+    json.as_u64().and_then(Timestamp::from_unix_nanos)
+}
+
+fn decode_resource(json: &Json) -> Option<Resource> {
+    let mut r = Resource::default();
+    for (k, v) in json.as_object()? {
+        let value = match v {
+            Json::Bool(x) => ResourceValue::Bool(*x),
+            Json::Number(x) => match x.as_i64() {
+                Some(x) => ResourceValue::Int(x),
+                None => ResourceValue::Double(x.as_f64()?),
+            },
+            Json::String(x) => ResourceValue::String(x.clone()),
+            _ => return None,
+        };
+        r.try_upsert(k, value).ok()?;
+    }
+    Some(r)
+}
+
+fn decode_attributes(json: &Json) -> Option<HashMap<String, Value>> {
+    json.as_object()?
+        .iter()
+        .map(|(k, v)| decode_value(v).map(|v| (k.to_owned(), v)))
+        .collect()
+}
+
+fn decode_value(json: &Json) -> Option<Value> {
+    let (t, v) = json.as_object()?.iter().next()?;
+    match t.as_str() {
+        "boolValue" => v.as_bool().map(Value::Bool),
+        "intValue" => v.as_i64().map(Value::Int64),
+        "uintValue" => v.as_u64().map(Value::UInt64),
+        "doubleValue" => decode_double(v).map(Value::Float64),
+        "stringValue" => v.as_str().map(|x| Value::String(x.to_owned())),
+        "bytesValue" => decode_hex(v.as_str()?).map(Value::Bytes),
+        "boolArrayValue" => decode_array(v, Json::as_bool).map(Value::BoolArray),
+        "intArrayValue" => decode_array(v, Json::as_i64).map(Value::Int64Array),
+        "uintArrayValue" => decode_array(v, Json::as_u64).map(Value::UInt64Array),
+        "doubleArrayValue" => decode_array(v, decode_double).map(Value::Float64Array),
+        "stringArrayValue" => {
+            decode_array(v, |x| x.as_str().map(str::to_owned)).map(Value::StringArray)
+        }
+        _ => None,
+    }
+}
+
+fn decode_double(json: &Json) -> Option<f64> {
+    match json.as_str() {
+        Some("NaN") => Some(f64::NAN),
+        Some("Infinity") => Some(f64::INFINITY),
+        Some("-Infinity") => Some(f64::NEG_INFINITY),
+        Some(_) => None,
+        None => json.as_f64(),
+    }
+}
+
+fn decode_array<T, F>(json: &Json, f: F) -> Option<Vec<T>>
+where
+    F: Fn(&Json) -> Option<T>,
+{
+    json.as_array()?.iter().map(f).collect()
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    s.as_bytes()
+        .chunks(2)
+        .map(|x| {
+            std::str::from_utf8(x)
+                .ok()
+                .filter(|x| x.len() == 2)
+                .and_then(|x| u8::from_str_radix(x, 16).ok())
+        })
+        .collect()
+}
+
+fn decode_event(json: &Json) -> Option<TimedEvent> {
+    let mut event = TimedEvent::new_with_timestamp(
+        decode_timestamp(json.get("timeUnixNano")?)?,
+        Event::new_with_attributes(
+            json.get("name")?.as_str()?,
+            decode_attributes(json.get("attributes")?)?,
+        ),
+    );
+    event.dropped_attributes_count = decode_count(json.get("droppedAttributesCount"))?;
+    Some(event)
+}
+
+fn decode_link(json: &Json) -> Option<ImmutableLink> {
+    Some(ImmutableLink {
+        span_context: ImmutableSpanContext {
+            trace_id: TraceId::try_from(json.get("traceId")?.as_str()?).ok()?,
+            span_id: SpanId::try_from(json.get("spanId")?.as_str()?).ok()?,
+            trace_option: match json.get("traceOption") {
+                Some(x) => TraceOption::try_from_base16(x.as_str()?)?,
+                None => TraceOption::empty(),
+            },
+            trace_state: match json.get("traceState") {
+                Some(x) => TraceState::try_from_http_text(x.as_str()?).ok()?,
+                None => TraceState::empty(),
+            },
+        },
+        attributes: decode_attributes(json.get("attributes")?)?,
+        dropped_attributes_count: decode_count(json.get("droppedAttributesCount"))?,
+    })
+}
+
+#[test]
+fn json_encode_span() {
+    use crate::api::trace::status::Status;
+    use crate::exporter::test_span_data;
+
+    let mut r = Resource::default();
+    r.try_upsert("service.name", "frontend").unwrap();
+    let mut s = test_span_data(&r, "get", 2, Some(1), 1, 3);
+    s.attributes.insert("retry".to_owned(), Value::UInt64(3));
+    s.status = Status::not_found();
+
+    let e = json!({
+        "traceId": "0000000000000000000000000000002a",
+        "spanId": "0000000000000002",
+        "parentSpanId": "0000000000000001",
+        "traceOption": "01",
+        "name": "get",
+        "kind": "INTERNAL",
+        "startTimeUnixNano": 1_000,
+        "endTimeUnixNano": 3_000,
+        "resource": {"service.name": "frontend"},
+        "attributes": {"retry": {"uintValue": 3}},
+        "events": [],
+        "links": [],
+        "status": {"code": 5},
+    });
+    assert_eq!(encode_span(&s), e);
+}
+
+#[test]
+fn json_round_trip() {
+    use crate::exporter::test_span_data;
+
+    let mut r = Resource::default();
+    r.try_upsert("service.name", "frontend").unwrap();
+    let r = r.with_schema_url("https://opentelemetry.io/schemas/1.9.0");
+    let mut s = test_span_data(&r, "get", 2, Some(1), 1, 3);
+    s.kind = SpanKind::SERVER;
+    s.attributes.insert("a".to_owned(), Value::Int64(-1));
+    s.attributes.insert("b".to_owned(), Value::Float64(0.5));
+    s.attributes
+        .insert("c".to_owned(), Value::Bytes(vec![0, 0xff]));
+    s.attributes
+        .insert("d".to_owned(), Value::from(vec!["x", "y"]));
+    s.attributes.insert(
+        "e".to_owned(),
+        Value::Float64Array(vec![0.5, f64::INFINITY, f64::NEG_INFINITY]),
+    );
+    s.attributes
+        .insert("f".to_owned(), Value::Float64(f64::NAN));
+    s.events.push(TimedEvent::new_with_timestamp(
+        Timestamp::from_unix_nanos(2_000).unwrap(),
+        Event::new("retry"),
+    ));
+    s.status = Status::aborted().with_description("conflict".to_owned());
+    s.dropped_events_count = 7;
+    let mut link = ImmutableLink {
+        span_context: s.context.clone(),
+        attributes: HashMap::new(),
+        dropped_attributes_count: 0,
+    };
+    link.span_context.trace_state = TraceState::try_from_http_text("vendor=value").unwrap();
+    s.links.push(link);
+
+    let e = encode_span(&s);
+    let a = decode_span(&e).unwrap();
+    assert_eq!(encode_span(&a), e);
+    assert_eq!(a.parent_span_id(), s.parent_span_id());
+    assert_eq!(a.dropped_events_count(), 7);
+    assert_eq!(a.resource(), &r);
+    assert!(a.links[0]
+        .span_context
+        .trace_option
+        .contains(TraceOption::MASK_SAMPLE));
+    assert!(a.links[0].span_context.trace_state.has_entry());
+    assert!(decode_span(&json!({"name": "get"})).is_none());
+
+    let mut r = Resource::default();
+    r.try_upsert("load", f64::NAN).unwrap();
+    let mut s = test_span_data(&r, "get", 2, None, 1, 3);
+    s.dropped_events_count = 1;
+    let mut e = encode_span(&s);
+    assert!(decode_span(&e).is_some());
+    e["droppedEventsCount"] = json!(u64::from(u32::MAX) + 1);
+    assert!(decode_span(&e).is_none());
+}
@@ -0,0 +1,510 @@
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::api::metrics::InstrumentKind;
+use crate::api::unit::Unit;
+use crate::sdk::metrics::aggregator::Aggregation;
+use crate::sdk::metrics::exemplar::Exemplar;
+use crate::sdk::metrics::export::{MetricExporter, Record, Temporality};
+use crate::sdk::trace::export::ResultCode;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+/// Longest wait for a scraper to send its request or read the response.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Keeps the latest collection and renders it in the
+/// [Prometheus text format](https://prometheus.io/docs/instrumenting/exposition_formats/).
+///
+/// Clones share the same state, so one clone can be given to a `PeriodicCollector`
+/// while another serves scrapes.
+#[derive(Clone, Default)]
+pub struct PrometheusExporter {
+    latest: Arc<Mutex<Vec<Record>>>,
+}
+
+impl PrometheusExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renders the latest collection.
+    pub fn encode(&self) -> String {
+        encode(&self.latest.lock().unwrap())
+    }
+
+    /// Renders the latest collection as OpenMetrics, with exemplars.
+    pub fn encode_openmetrics(&self) -> String {
+        encode_openmetrics(&self.latest.lock().unwrap())
+    }
+
+    /// Answers one HTTP request: `GET /metrics` gets the latest collection, as OpenMetrics
+    /// if the `Accept` header asks for it, and anything else a 404.
+    pub fn handle(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut openmetrics = false;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line == "\r\n" || line == "\n" {
+                break;
+            }
+            let line = line.to_ascii_lowercase();
+            if line.starts_with("accept:") && line.contains("application/openmetrics-text") {
+                openmetrics = true;
+            }
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (status, content_type, body) = match (parts.next(), parts.next()) {
+            (Some("GET"), Some("/metrics")) if openmetrics => (
+                "200 OK",
+                OPENMETRICS_CONTENT_TYPE,
+                self.encode_openmetrics(),
+            ),
+            (Some("GET"), Some("/metrics")) => ("200 OK", CONTENT_TYPE, self.encode()),
+            _ => ("404 Not Found", CONTENT_TYPE, String::new()),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+
+    /// Serves `/metrics` on `addr` from a background thread, answering each connection on
+    /// its own thread so a slow scraper cannot hold up the others.
+    pub fn serve<A: ToSocketAddrs>(&self, addr: A) -> io::Result<JoinHandle<()>> {
+        let listener = TcpListener::bind(addr)?;
+        let exporter = self.clone();
+        Ok(thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let exporter = exporter.clone();
+                thread::spawn(move || exporter.handle(stream));
+            }
+        }))
+    }
+}
+
+impl MetricExporter for PrometheusExporter {
+    fn temporality(&self) -> Temporality {
+        Temporality::Cumulative
+    }
+
+    fn export(&mut self, batch: &[Record]) -> ResultCode {
+        *self.latest.lock().unwrap() = batch.to_vec();
+        ResultCode::Success
+    }
+
+    fn shutdown(&mut self) {}
+}
+
+/// Renders `records`, grouped by metric name and sorted by name and labels.
+pub fn encode(records: &[Record]) -> String {
+    encode_with(records, false)
+}
+
+/// Renders `records` in the [OpenMetrics](https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md)
+/// format, with the exemplars of counters and histogram buckets.
+pub fn encode_openmetrics(records: &[Record]) -> String {
+    let mut out = encode_with(records, true);
+    out.push_str("# EOF\n");
+    out
+}
+
+fn encode_with(records: &[Record], openmetrics: bool) -> String {
+    let mut records: Vec<(String, &Record)> = records.iter().map(|x| (metric_name(x), x)).collect();
+    records.sort_by(|(a, x), (b, y)| a.cmp(b).then_with(|| x.labels().cmp(y.labels())));
+
+    let mut out = String::new();
+    let mut previous: Option<&str> = None;
+    for (name, record) in records.iter() {
+        if previous != Some(name.as_str()) {
+            let kind = metric_type(record);
+            // OpenMetrics names the counter family without the `_total` of its sample.
+            let family = match name.strip_suffix("_total") {
+                Some(x) if openmetrics && kind == "counter" => x,
+                _ => name.as_str(),
+            };
+            let description = record.descriptor().variable().description();
+            if !description.is_empty() {
+                writeln!(out, "# HELP {} {}", family, escape_help(description)).unwrap();
+            }
+            writeln!(out, "# TYPE {} {}", family, kind).unwrap();
+            previous = Some(name.as_str());
+        }
+        write_samples(&mut out, name, record, openmetrics);
+    }
+    out
+}
+
+fn metric_name(record: &Record) -> String {
+    let descriptor = record.descriptor();
+    let mut name = sanitize(descriptor.name());
+    let suffix = unit_suffix(descriptor.variable().unit());
+    if !name.ends_with(&suffix) {
+        name.push_str(&suffix);
+    }
+    if let Aggregation::Sum(_) = record.aggregation() {
+        if descriptor.kind().is_monotonic() && !name.ends_with("_total") {
+            name.push_str("_total");
+        }
+    }
+    name
+}
+
+/// Suffix naming the base unit, e.g. `_seconds` for `ms` or `_bytes_per_second` for `KiBy/s`.
+fn unit_suffix(unit: &Unit) -> String {
+    match unit {
+        Unit::Percent => "_ratio".to_owned(),
+        Unit::PerSecond(x) => format!("{}_per_second", unit_suffix(x)),
+        _ => match unit.base() {
+            Unit::Seconds => "_seconds".to_owned(),
+            Unit::Bytes => "_bytes".to_owned(),
+            _ => String::new(),
+        },
+    }
+}
+
+fn metric_type(record: &Record) -> &'static str {
+    match record.aggregation() {
+        Aggregation::Sum(_) if record.descriptor().kind() == InstrumentKind::Counter => "counter",
+        Aggregation::Sum(_) | Aggregation::LastValue { .. } => "gauge",
+        Aggregation::MinMaxSumCount { .. } => "summary",
+        Aggregation::Histogram { .. } => "histogram",
+    }
+}
+
+fn write_samples(out: &mut String, name: &str, record: &Record, openmetrics: bool) {
+    let labels: Vec<(String, String)> = record
+        .labels()
+        .iter()
+        .map(|(k, v)| (sanitize(k.value()), escape_label_value(v.value())))
+        .collect();
+    let unit = record.descriptor().variable().unit();
+    let base = unit.base();
+    let scale = |x: f64| unit.convert(x, &base).unwrap_or(x);
+    let exemplars = if openmetrics { record.exemplars() } else { &[] };
+    let mut sample =
+        |suffix: &str, extra: Option<(&str, String)>, value: f64, exemplar: Option<&Exemplar>| {
+            let mut pairs: Vec<String> = labels
+                .iter()
+                .map(|(k, v)| format!("{}=\"{}\"", k, v))
+                .collect();
+            if let Some((k, v)) = extra {
+                pairs.push(format!("{}=\"{}\"", k, v));
+            }
+            write!(out, "{}{}", name, suffix).unwrap();
+            if !pairs.is_empty() {
+                write!(out, "{{{}}}", pairs.join(",")).unwrap();
+            }
+            write!(out, " {}", format_value(value)).unwrap();
+            if let Some(e) = exemplar {
+                write_exemplar(out, e, scale(e.value()));
+            }
+            out.push('\n');
+        };
+
+    match record.aggregation() {
+        Aggregation::Sum(x) if record.descriptor().kind() == InstrumentKind::Counter => {
+            sample("", None, scale(*x), exemplars.last())
+        }
+        Aggregation::Sum(x) => sample("", None, scale(*x), None),
+        Aggregation::LastValue { value, .. } => sample("", None, scale(*value), None),
+        Aggregation::MinMaxSumCount { sum, count, .. } => {
+            sample("_sum", None, scale(*sum), None);
+            sample("_count", None, *count as f64, None);
+        }
+        Aggregation::Histogram {
+            boundaries,
+            counts,
+            sum,
+            count,
+        } => {
+            let in_bucket = |i: usize| {
+                exemplars.iter().rev().find(|x| {
+                    let above = i == 0 || x.value() > boundaries[i - 1];
+                    above && boundaries.get(i).map_or(true, |b| x.value() <= *b)
+                })
+            };
+            let mut cumulative = 0;
+            for (i, (bound, n)) in boundaries.iter().zip(counts.iter()).enumerate() {
+                cumulative += n;
+                sample(
+                    "_bucket",
+                    Some(("le", format_value(scale(*bound)))),
+                    cumulative as f64,
+                    in_bucket(i),
+                );
+            }
+            sample(
+                "_bucket",
+                Some(("le", "+Inf".to_owned())),
+                *count as f64,
+                in_bucket(boundaries.len()),
+            );
+            sample("_sum", None, scale(*sum), None);
+            sample("_count", None, *count as f64, None);
+        }
+    }
+}
+
+/// ` # {trace_id="..",span_id="..",...} value timestamp`, the timestamp in seconds.
+fn write_exemplar(out: &mut String, exemplar: &Exemplar, value: f64) {
+    let mut pairs = vec![
+        format!("trace_id=\"{}\"", exemplar.trace_id()),
+        format!("span_id=\"{}\"", exemplar.span_id()),
+    ];
+    pairs.extend(exemplar.filtered_labels().iter().map(|(k, v)| {
+        format!(
+            "{}=\"{}\"",
+            sanitize(k.value()),
+            escape_label_value(v.value())
+        )
+    }));
+    write!(out, " # {{{}}} {}", pairs.join(","), format_value(value)).unwrap();
+    if let Some(ms) = exemplar.timestamp().as_millis() {
+        write!(out, " {}.{:03}", ms / 1000, ms % 1000).unwrap();
+    }
+}
+
+/// Replaces characters outside `[a-zA-Z0-9_:]` with `_`.
+fn sanitize(name: &str) -> String {
+    let mut s: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == ':' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if s.starts_with(|c: char| c.is_ascii_digit()) {
+        s.insert(0, '_');
+    }
+    s
+}
+
+fn escape_help(s: &str) -> String {
+    s.replace('\\', r"\\").replace('\n', r"\n")
+}
+
+fn escape_label_value(s: &str) -> String {
+    escape_help(s).replace('"', "\\\"")
+}
+
+fn format_value(x: f64) -> String {
+    if x.is_nan() {
+        "NaN".to_owned()
+    } else if x.is_infinite() {
+        if x > 0.0 { "+Inf" } else { "-Inf" }.to_owned()
+    } else {
+        x.to_string()
+    }
+}
+
+#[test]
+fn prometheus_encode() {
+    use crate::api::metrics::labels::LabelSet;
+    use crate::api::metrics::Descriptor;
+    use crate::api::registry::Variable;
+    use crate::api::trace::Timestamp;
+
+    let record = |variable: Variable, kind, labels: &[(&str, &str)], aggregation| Record {
+        descriptor: Arc::new(Descriptor::new(variable, kind)),
+        labels: LabelSet::try_from_pairs(labels).unwrap(),
+        aggregation,
+        start_time: Timestamp::from_unix_nanos(0).unwrap(),
+        end_time: Timestamp::from_unix_nanos(0).unwrap(),
+        exemplars: Vec::new(),
+    };
+    let requests = Variable::new("http.requests", Unit::Dimensionless)
+        .with_description("Requests served.\nBy method.");
+    let mut latency = Aggregation::histogram(&[10.0, 100.0]);
+    for x in &[5.0, 50.0, 500.0] {
+        latency.update(*x, Timestamp::from_unix_nanos(0).unwrap());
+    }
+    let records = vec![
+        record(
+            requests.clone(),
+            InstrumentKind::Counter,
+            &[("method", "POST")],
+            Aggregation::Sum(2.0),
+        ),
+        record(
+            Variable::new("latency", Unit::Milliseconds),
+            InstrumentKind::Histogram,
+            &[],
+            latency,
+        ),
+        record(
+            requests,
+            InstrumentKind::Counter,
+            &[("method", "GET"), ("path", "/a\"b\\")],
+            Aggregation::Sum(1.5),
+        ),
+        record(
+            Variable::new("heap", Unit::Bytes),
+            InstrumentKind::Observer,
+            &[],
+            Aggregation::LastValue {
+                value: 1024.0,
+                timestamp: None,
+            },
+        ),
+    ];
+
+    assert_eq!(
+        encode(&records),
+        r#"# TYPE heap_bytes gauge
+heap_bytes 1024
+# HELP http_requests_total Requests served.\nBy method.
+# TYPE http_requests_total counter
+http_requests_total{method="GET",path="/a\"b\\"} 1.5
+http_requests_total{method="POST"} 2
+# TYPE latency_seconds histogram
+latency_seconds_bucket{le="0.01"} 1
+latency_seconds_bucket{le="0.1"} 2
+latency_seconds_bucket{le="+Inf"} 3
+latency_seconds_sum 0.555
+latency_seconds_count 3
+"#
+    );
+}
+
+#[test]
+fn prometheus_serve() {
+    use crate::api::metrics::labels::LabelSet;
+    use crate::api::registry::Variable;
+    use crate::sdk::metrics::{Accumulator, PeriodicCollector};
+    use std::io::Read;
+
+    let exporter = PrometheusExporter::new();
+    let mut collector = PeriodicCollector::new(Arc::new(Accumulator::new()), exporter.clone());
+    let jobs = collector
+        .meter()
+        .new_counter(Variable::new("jobs", Unit::Dimensionless));
+    jobs.add(3.0, &LabelSet::default());
+    collector.collect();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = {
+        let exporter = exporter.clone();
+        thread::spawn(move || {
+            for _ in 0..3 {
+                let (stream, _) = listener.accept().unwrap();
+                exporter.handle(stream).unwrap();
+            }
+        })
+    };
+    let get_with = |path: &str, headers: &str| {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: localhost\r\n{}\r\n",
+            path, headers
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    };
+    let get = |path: &str| get_with(path, "");
+
+    let metrics = get("/metrics");
+    assert!(metrics.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(metrics.contains("Content-Type: text/plain; version=0.0.4\r\n"));
+    assert!(metrics.ends_with("# TYPE jobs_total counter\njobs_total 3\n"));
+    assert!(get("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    let openmetrics = get_with("/metrics", "Accept: application/openmetrics-text\r\n");
+    assert!(openmetrics.contains("Content-Type: application/openmetrics-text; version=1.0.0"));
+    assert!(openmetrics.ends_with("# TYPE jobs counter\njobs_total 3\n# EOF\n"));
+    server.join().unwrap();
+
+    // An idle connection does not block scrapes from others.
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    exporter.serve(addr).unwrap();
+    let _idle = TcpStream::connect(addr).unwrap();
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.ends_with("jobs_total 3\n"));
+}
+
+#[test]
+fn prometheus_openmetrics_exemplars() {
+    use crate::api::metrics::labels::LabelSet;
+    use crate::api::metrics::Descriptor;
+    use crate::api::registry::Variable;
+    use crate::api::trace::span_context::{SpanId, TraceId};
+    use crate::api::trace::Timestamp;
+    use std::num::{NonZeroU128, NonZeroU64};
+
+    let exemplar = |value, span_id, millis| Exemplar {
+        value,
+        timestamp: Timestamp::from_unix_millis(millis).unwrap(),
+        trace_id: TraceId::new(NonZeroU128::new(42).unwrap()),
+        span_id: SpanId::new(NonZeroU64::new(span_id).unwrap()),
+        filtered_labels: LabelSet::default(),
+    };
+    let record = |variable: Variable, kind, aggregation, exemplars| Record {
+        descriptor: Arc::new(Descriptor::new(variable, kind)),
+        labels: LabelSet::default(),
+        aggregation,
+        start_time: Timestamp::from_unix_nanos(0).unwrap(),
+        end_time: Timestamp::from_unix_nanos(0).unwrap(),
+        exemplars,
+    };
+    let mut latency = Aggregation::histogram(&[100.0]);
+    latency.update(50.0, Timestamp::from_unix_nanos(0).unwrap());
+    latency.update(250.0, Timestamp::from_unix_nanos(0).unwrap());
+    let records = vec![
+        record(
+            Variable::new("jobs", Unit::Dimensionless).with_description("Jobs run."),
+            InstrumentKind::Counter,
+            Aggregation::Sum(2.0),
+            vec![exemplar(1.0, 1, 1_500), exemplar(1.0, 2, 2_000)],
+        ),
+        record(
+            Variable::new("latency", Unit::Milliseconds),
+            InstrumentKind::Histogram,
+            latency,
+            vec![exemplar(250.0, 3, 3_250)],
+        ),
+    ];
+
+    assert_eq!(
+        encode_openmetrics(&records),
+        r#"# HELP jobs Jobs run.
+# TYPE jobs counter
+jobs_total 2 # {trace_id="0000000000000000000000000000002a",span_id="0000000000000002"} 1 2.000
+# TYPE latency_seconds histogram
+latency_seconds_bucket{le="0.1"} 1
+latency_seconds_bucket{le="+Inf"} 2 # {trace_id="0000000000000000000000000000002a",span_id="0000000000000003"} 0.25 3.250
+latency_seconds_sum 0.3
+latency_seconds_count 2
+# EOF
+"#
+    );
+    assert!(!encode(&records).contains("trace_id"));
+}
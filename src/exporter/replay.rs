@@ -0,0 +1,222 @@
+//! Reads span files written by `FileExporter` and rebuilds traces from them.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::api::trace::span_context::{SpanId, TraceId};
+use crate::api::trace::span_data::SpanData;
+use crate::exporter::file::generation_path;
+use crate::exporter::json;
+use crate::sdk::trace::export::{ResultCode, SpanExporter};
+
+/// Reads one JSON-lines span file. Blank lines are skipped, malformed ones are an error.
+pub fn read_spans<P: AsRef<Path>>(path: P) -> io::Result<Vec<SpanData>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut spans = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let span = serde_json::from_str(&line)
+            .ok()
+            .and_then(|x| json::decode_span(&x))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: not a span", i + 1),
+                )
+            })?;
+        spans.push(span);
+    }
+    Ok(spans)
+}
+
+/// Reads the active file and all of its rotated generations, oldest first.
+pub fn read_rotated<P: AsRef<Path>>(path: P) -> io::Result<Vec<SpanData>> {
+    let path = path.as_ref();
+    let generations = (1..)
+        .take_while(|n| generation_path(path, *n).exists())
+        .count();
+
+    let mut spans = Vec::new();
+    for n in (0..=generations).rev() {
+        let p = generation_path(path, n);
+        if p.exists() {
+            spans.extend(read_spans(p)?);
+        }
+    }
+    Ok(spans)
+}
+
+/// Spans sharing one `TraceId`, sorted by start time.
+pub struct Trace {
+    trace_id: TraceId,
+    spans: Vec<SpanData>,
+}
+
+impl Trace {
+    /// Groups spans by trace, keeping traces in order of their first span.
+    pub fn assemble(spans: Vec<SpanData>) -> Vec<Trace> {
+        let mut traces: Vec<Trace> = Vec::new();
+        for span in spans {
+            match traces.iter_mut().find(|x| &x.trace_id == span.trace_id()) {
+                Some(t) => t.spans.push(span),
+                None => traces.push(Trace {
+                    trace_id: span.trace_id().clone(),
+                    spans: vec![span],
+                }),
+            }
+        }
+        for t in traces.iter_mut() {
+            t.spans.sort_by_key(|x| x.start_time().as_nanos());
+        }
+        traces
+    }
+
+    pub fn trace_id(&self) -> &TraceId {
+        &self.trace_id
+    }
+
+    pub fn spans(&self) -> &[SpanData] {
+        &self.spans
+    }
+
+    /// Spans without a parent, or whose parent was not recorded.
+    pub fn roots(&self) -> Vec<&SpanData> {
+        self.spans
+            .iter()
+            .filter(|x| match x.parent_span_id() {
+                Some(p) => !self.spans.iter().any(|y| y.span_id() == p),
+                None => true,
+            })
+            .collect()
+    }
+
+    pub fn children(&self, span_id: &SpanId) -> Vec<&SpanData> {
+        self.spans
+            .iter()
+            .filter(|x| x.parent_span_id() == Some(span_id))
+            .collect()
+    }
+
+    /// Time from the earliest start to the latest end.
+    pub fn duration_as_micros(&self) -> u128 {
+        let start = self
+            .spans
+            .iter()
+            .filter_map(|x| x.start_time().as_micros())
+            .min();
+        let end = self
+            .spans
+            .iter()
+            .filter_map(|x| x.end_time().as_micros())
+            .max();
+        match (start, end) {
+            (Some(s), Some(e)) if e > s => e - s,
+            _ => 0,
+        }
+    }
+
+    /// Follows the last-finishing child from the longest root down to a leaf.
+    ///
+    /// Each span id is visited once, so self-parented spans and duplicate ids cannot loop.
+    pub fn critical_path(&self) -> Vec<&SpanData> {
+        let mut path = Vec::new();
+        let mut visited = HashSet::new();
+        let mut next = self
+            .roots()
+            .into_iter()
+            .max_by_key(|x| span_duration_as_micros(x));
+        while let Some(span) = next {
+            visited.insert(span.span_id());
+            path.push(span);
+            next = self
+                .children(span.span_id())
+                .into_iter()
+                .filter(|x| !visited.contains(x.span_id()))
+                .max_by_key(|x| x.end_time().as_nanos());
+        }
+        path
+    }
+}
+
+pub fn span_duration_as_micros(span: &SpanData) -> u128 {
+    span.end_time()
+        .duration_since_as_micros(span.start_time())
+        .unwrap_or(0)
+}
+
+/// Sends recorded spans through `exporter` in batches of `batch_size`, stopping at the first failure.
+pub fn replay(
+    spans: &[SpanData],
+    exporter: &mut dyn SpanExporter,
+    batch_size: usize,
+) -> ResultCode {
+    for batch in spans.chunks(batch_size.max(1)) {
+        let result = exporter.export(batch);
+        if result != ResultCode::Success {
+            return result;
+        }
+    }
+    ResultCode::Success
+}
+
+#[test]
+fn replay_read_and_assemble() {
+    use crate::api::resources::Resource;
+    use crate::exporter::file::{test_dir, FileExporter, Rotation};
+    use crate::exporter::in_memory::InMemorySpanExporter;
+    use crate::exporter::test_span_data;
+    use std::fs;
+
+    let dir = test_dir("replay");
+    let path = dir.join("spans.jsonl");
+    let r = Resource::default();
+    let mut exporter = FileExporter::new(&path)
+        .unwrap()
+        .with_rotation(Rotation::Size(1));
+    exporter.export(&[test_span_data(&r, "root", 1, None, 0, 10_000)]);
+    exporter.export(&[
+        test_span_data(&r, "fast", 2, Some(1), 1_000, 3_000),
+        test_span_data(&r, "slow", 3, Some(1), 2_000, 9_000),
+    ]);
+    exporter.export(&[test_span_data(&r, "db", 4, Some(3), 2_500, 8_000)]);
+    exporter.shutdown();
+
+    assert_eq!(read_spans(&path).unwrap().len(), 1);
+    let spans = read_rotated(&path).unwrap();
+    assert_eq!(spans.len(), 4);
+
+    let traces = Trace::assemble(spans.clone());
+    assert_eq!(traces.len(), 1);
+    let t = &traces[0];
+    assert_eq!(t.duration_as_micros(), 10_000);
+    assert_eq!(t.roots().len(), 1);
+    let names: Vec<&str> = t.critical_path().iter().map(|x| x.name()).collect();
+    assert_eq!(names, vec!["root", "slow", "db"]);
+
+    let mut collector = InMemorySpanExporter::new();
+    assert_eq!(replay(&spans, &mut collector, 3), ResultCode::Success);
+    assert_eq!(collector.finished_spans().len(), 4);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn critical_path_ignores_cycles() {
+    use crate::api::resources::Resource;
+    use crate::exporter::test_span_data;
+
+    let r = Resource::default();
+    let spans = vec![
+        test_span_data(&r, "root", 1, None, 0, 100),
+        test_span_data(&r, "child", 2, Some(1), 10, 90),
+        test_span_data(&r, "self-parented", 2, Some(2), 20, 95),
+        test_span_data(&r, "leaf", 3, Some(2), 30, 80),
+    ];
+    let traces = Trace::assemble(spans);
+    let names: Vec<&str> = traces[0].critical_path().iter().map(|x| x.name()).collect();
+    assert_eq!(names, vec!["root", "child", "leaf"]);
+}
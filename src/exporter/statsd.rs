@@ -0,0 +1,455 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+use rand::Rng;
+
+use crate::api::resources::Resource;
+use crate::sdk::metrics::aggregator::Aggregation;
+use crate::sdk::metrics::export::{MetricExporter, Record, Temporality};
+use crate::sdk::trace::export::ResultCode;
+
+const DEFAULT_MAX_PACKET_SIZE: usize = 1432;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Plain `name:value|type` lines; labels are dropped.
+    Statsd,
+    /// `name:value|type|#tag:value,...` with tags from the labels and the `Resource`.
+    DogStatsd,
+}
+
+/// Pushes deltas to a [StatsD](https://github.com/statsd/statsd/blob/master/docs/metric_types.md)
+/// or [DogStatsD](https://docs.datadoghq.com/developers/dogstatsd/datagram_shell/) agent over UDP.
+///
+/// Sums are sent as counters, last values as gauges. A negative gauge is preceded by a `:0|g`
+/// line, as agents read a signed gauge value as a change of the current one.
+///
+/// Histograms are sent as `<name>.sum` and `<name>.count` counters, and one `<name>.bucket`
+/// counter per bucket of the values up to its bound: tagged `le:<bound>` in DogStatsD, or
+/// named `<name>.bucket.le_<bound>` in plain StatsD. Min/max/sum/count aggregations are sent
+/// as `<name>.min` and `<name>.max` gauges with the same two counters. Aggregations keep no
+/// recorded values, so no `|h`, `|ms` or `|d` lines are sent.
+pub struct StatsdExporter {
+    socket: UdpSocket,
+    target: SocketAddr,
+    format: Format,
+    prefix: String,
+    sample_rate: f64,
+    max_packet_size: usize,
+    tags: BTreeMap<String, String>,
+}
+
+impl StatsdExporter {
+    /// `target` is the agent address, e.g. `127.0.0.1:8125`.
+    pub fn new<A: ToSocketAddrs>(target: A, format: Format) -> io::Result<Self> {
+        let target = target
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address to send to"))?;
+        let local: SocketAddr = if target.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+        Ok(Self {
+            socket: UdpSocket::bind(local)?,
+            target,
+            format,
+            prefix: String::new(),
+            sample_rate: 1.0,
+            max_packet_size: DEFAULT_MAX_PACKET_SIZE,
+            tags: BTreeMap::new(),
+        })
+    }
+
+    /// Prepended to every metric name with a `.` separator.
+    pub fn with_prefix(self, prefix: &str) -> Self {
+        Self {
+            prefix: if prefix.is_empty() {
+                String::new()
+            } else {
+                format!("{}.", sanitize(prefix))
+            },
+            ..self
+        }
+    }
+
+    /// Fraction of counter lines sent, tagged with `|@rate` so the agent scales them back up.
+    /// Clamped to `(0, 1]`.
+    pub fn with_sample_rate(self, sample_rate: f64) -> Self {
+        let sample_rate = if sample_rate > 0.0 && sample_rate < 1.0 {
+            sample_rate
+        } else {
+            1.0
+        };
+        Self {
+            sample_rate,
+            ..self
+        }
+    }
+
+    /// Lines are batched into datagrams of at most this many bytes; a longer line is sent alone.
+    pub fn with_max_packet_size(self, max_packet_size: usize) -> Self {
+        Self {
+            max_packet_size,
+            ..self
+        }
+    }
+
+    /// Adds the labels of `resource` as tags of every line; labels of a record take precedence.
+    pub fn with_resource(self, resource: &Resource) -> Self {
+        let mut tags = self.tags;
+        tags.extend(
+            resource
+                .labels()
+                .map(|(k, v)| (sanitize(k.value()), sanitize(&v.to_string()))),
+        );
+        Self { tags, ..self }
+    }
+
+    /// The lines of one record, without sampling.
+    pub fn encode(&self, record: &Record) -> Vec<String> {
+        let name = format!("{}{}", self.prefix, sanitize(record.descriptor().name()));
+        let tags = self.encode_tags(record, None);
+        let line = |suffix: &str, value: f64, kind: &str| {
+            format!("{}{}:{}|{}{}", name, suffix, value, kind, tags)
+        };
+        let gauge = |suffix: &str, value: f64| {
+            let mut lines = Vec::with_capacity(2);
+            if value < 0.0 {
+                lines.push(line(suffix, 0.0, "g"));
+            }
+            lines.push(line(suffix, value, "g"));
+            lines
+        };
+
+        match record.aggregation() {
+            Aggregation::Sum(x) => vec![line("", *x, "c")],
+            Aggregation::LastValue { value, .. } => gauge("", *value),
+            Aggregation::MinMaxSumCount {
+                min,
+                max,
+                sum,
+                count,
+            } => {
+                let mut lines = gauge(".min", *min);
+                lines.extend(gauge(".max", *max));
+                lines.push(line(".sum", *sum, "c"));
+                lines.push(line(".count", *count as f64, "c"));
+                lines
+            }
+            Aggregation::Histogram {
+                boundaries,
+                counts,
+                sum,
+                count,
+            } => {
+                let mut lines = vec![line(".sum", *sum, "c"), line(".count", *count as f64, "c")];
+                let mut cumulative = 0;
+                for (i, n) in counts.iter().enumerate() {
+                    cumulative += n;
+                    let le = boundaries
+                        .get(i)
+                        .map_or_else(|| "+Inf".to_owned(), |x| x.to_string());
+                    lines.push(match self.format {
+                        Format::Statsd => format!(
+                            "{}.bucket.le_{}:{}|c",
+                            name,
+                            le.replace('.', "_").replace('+', ""),
+                            cumulative
+                        ),
+                        Format::DogStatsd => format!(
+                            "{}.bucket:{}|c{}",
+                            name,
+                            cumulative,
+                            self.encode_tags(record, Some(&le))
+                        ),
+                    });
+                }
+                lines
+            }
+        }
+    }
+
+    /// `le` is the bound of a histogram bucket line.
+    fn encode_tags(&self, record: &Record, le: Option<&str>) -> String {
+        if self.format == Format::Statsd {
+            return String::new();
+        }
+        let mut tags = self.tags.clone();
+        tags.extend(
+            record
+                .labels()
+                .iter()
+                .map(|(k, v)| (sanitize(k.value()), sanitize(v.value()))),
+        );
+        if let Some(x) = le {
+            tags.insert("le".to_owned(), x.to_owned());
+        }
+        if tags.is_empty() {
+            return String::new();
+        }
+        let pairs: Vec<String> = tags
+            .iter()
+            .map(|(k, v)| {
+                if v.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}:{}", k, v)
+                }
+            })
+            .collect();
+        format!("|#{}", pairs.join(","))
+    }
+
+    /// Applies the sample rate to a counter line: `None` if dropped.
+    fn sample(&self, line: String) -> Option<String> {
+        if self.sample_rate >= 1.0 || line.split('|').nth(1) != Some("c") {
+            return Some(line);
+        }
+        if !rand::thread_rng().gen_bool(self.sample_rate) {
+            return None;
+        }
+        Some(match line.find("|#") {
+            Some(i) => format!("{}|@{}{}", &line[..i], self.sample_rate, &line[i..]),
+            None => format!("{}|@{}", line, self.sample_rate),
+        })
+    }
+
+    fn send(&self, lines: impl Iterator<Item = String>) -> io::Result<()> {
+        let mut packet = String::new();
+        for line in lines {
+            if !packet.is_empty() && packet.len() + 1 + line.len() > self.max_packet_size {
+                self.socket.send_to(packet.as_bytes(), self.target)?;
+                packet.clear();
+            }
+            if !packet.is_empty() {
+                packet.push('\n');
+            }
+            packet.push_str(&line);
+        }
+        if !packet.is_empty() {
+            self.socket.send_to(packet.as_bytes(), self.target)?;
+        }
+        Ok(())
+    }
+}
+
+impl MetricExporter for StatsdExporter {
+    fn temporality(&self) -> Temporality {
+        Temporality::Delta
+    }
+
+    fn export(&mut self, batch: &[Record]) -> ResultCode {
+        let lines = batch
+            .iter()
+            .flat_map(|x| self.encode(x))
+            .filter_map(|x| self.sample(x));
+        match self.send(lines) {
+            Ok(()) => ResultCode::Success,
+            Err(_) => ResultCode::FailedRetryable,
+        }
+    }
+
+    fn shutdown(&mut self) {}
+}
+
+/// Replaces the characters with meaning in a line (`:|@#,` and whitespace) with `_`.
+fn sanitize(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            ':' | '|' | '@' | '#' | ',' => '_',
+            c if c.is_whitespace() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+#[test]
+fn statsd_export() {
+    use crate::api::metrics::labels::LabelSet;
+    use crate::api::registry::Variable;
+    use crate::api::unit::Unit;
+    use crate::sdk::metrics::aggregator::SimpleSelector;
+    use crate::sdk::metrics::{Accumulator, PeriodicCollector};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let agent = UdpSocket::bind("127.0.0.1:0").unwrap();
+    agent
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut resource = Resource::default();
+    resource.try_upsert("service.name", "shop").unwrap();
+    let exporter = StatsdExporter::new(agent.local_addr().unwrap(), Format::DogStatsd)
+        .unwrap()
+        .with_prefix("app")
+        .with_resource(&resource)
+        .with_max_packet_size(64);
+    let accumulator =
+        Accumulator::new().with_selector(Box::new(SimpleSelector::new().with_min_max_sum_count()));
+    let mut collector = PeriodicCollector::new(Arc::new(accumulator), exporter);
+    let meter = collector.meter();
+    let get = LabelSet::try_from_pairs(&[("method", "GET")]).unwrap();
+    meter
+        .new_counter(Variable::new("requests", Unit::Dimensionless))
+        .add(2.0, &get);
+    let latency = meter.new_histogram(Variable::new("latency", Unit::Milliseconds));
+    latency.record(4.0, &LabelSet::default());
+    latency.record(6.0, &LabelSet::default());
+    assert_eq!(collector.collect(), ResultCode::Success);
+
+    let mut lines = Vec::new();
+    let mut buf = [0; 1500];
+    while lines.len() < 5 {
+        let n = agent.recv(&mut buf).unwrap();
+        assert!(n <= 64);
+        let packet = std::str::from_utf8(&buf[..n]).unwrap();
+        lines.extend(packet.lines().map(str::to_owned));
+    }
+    lines.sort();
+    assert_eq!(
+        lines,
+        vec![
+            "app.latency.count:2|c|#service.name:shop",
+            "app.latency.max:6|g|#service.name:shop",
+            "app.latency.min:4|g|#service.name:shop",
+            "app.latency.sum:10|c|#service.name:shop",
+            "app.requests:2|c|#method:GET,service.name:shop",
+        ]
+    );
+}
+
+#[test]
+fn statsd_encode_plain() {
+    use crate::api::metrics::labels::LabelSet;
+    use crate::api::metrics::{Descriptor, InstrumentKind};
+    use crate::api::registry::Variable;
+    use crate::api::trace::Timestamp;
+    use crate::api::unit::Unit;
+    use std::sync::Arc;
+
+    let exporter = StatsdExporter::new("127.0.0.1:8125", Format::Statsd)
+        .unwrap()
+        .with_sample_rate(0.5);
+    let record = Record {
+        descriptor: Arc::new(Descriptor::new(
+            Variable::new("queue depth", Unit::Dimensionless),
+            InstrumentKind::Gauge,
+        )),
+        labels: LabelSet::try_from_pairs(&[("queue", "jobs")]).unwrap(),
+        aggregation: Aggregation::LastValue {
+            value: 3.0,
+            timestamp: None,
+        },
+        start_time: Timestamp::from_unix_nanos(0).unwrap(),
+        end_time: Timestamp::from_unix_nanos(0).unwrap(),
+        exemplars: Vec::new(),
+    };
+    assert_eq!(exporter.encode(&record), vec!["queue_depth:3|g"]);
+    assert_eq!(
+        exporter.sample("queue_depth:3|g".to_owned()),
+        Some("queue_depth:3|g".to_owned())
+    );
+}
+
+#[test]
+fn statsd_negative_gauges_reset_first() {
+    use crate::api::metrics::labels::LabelSet;
+    use crate::api::metrics::{Descriptor, InstrumentKind};
+    use crate::api::registry::Variable;
+    use crate::api::trace::Timestamp;
+    use crate::api::unit::Unit;
+    use std::sync::Arc;
+
+    let exporter = StatsdExporter::new("127.0.0.1:8125", Format::Statsd).unwrap();
+    let record = |kind, aggregation| Record {
+        descriptor: Arc::new(Descriptor::new(
+            Variable::new("temperature", Unit::Dimensionless),
+            kind,
+        )),
+        labels: LabelSet::default(),
+        aggregation,
+        start_time: Timestamp::from_unix_nanos(0).unwrap(),
+        end_time: Timestamp::from_unix_nanos(0).unwrap(),
+        exemplars: Vec::new(),
+    };
+    let last = record(
+        InstrumentKind::Gauge,
+        Aggregation::LastValue {
+            value: -3.0,
+            timestamp: None,
+        },
+    );
+    assert_eq!(
+        exporter.encode(&last),
+        vec!["temperature:0|g", "temperature:-3|g"]
+    );
+    let summary = record(
+        InstrumentKind::Histogram,
+        Aggregation::MinMaxSumCount {
+            min: -2.0,
+            max: 5.0,
+            sum: 3.0,
+            count: 2,
+        },
+    );
+    assert_eq!(
+        exporter.encode(&summary),
+        vec![
+            "temperature.min:0|g",
+            "temperature.min:-2|g",
+            "temperature.max:5|g",
+            "temperature.sum:3|c",
+            "temperature.count:2|c",
+        ]
+    );
+}
+
+#[test]
+fn statsd_histogram_buckets() {
+    use crate::api::metrics::labels::LabelSet;
+    use crate::api::metrics::{Descriptor, InstrumentKind};
+    use crate::api::registry::Variable;
+    use crate::api::trace::Timestamp;
+    use crate::api::unit::Unit;
+    use std::sync::Arc;
+
+    let mut aggregation = Aggregation::histogram(&[0.5, 10.0]);
+    for x in [0.25, 4.0, 6.0, 20.0].iter() {
+        aggregation.update(*x, Timestamp::from_unix_nanos(0).unwrap());
+    }
+    let record = Record {
+        descriptor: Arc::new(Descriptor::new(
+            Variable::new("latency", Unit::Milliseconds),
+            InstrumentKind::Histogram,
+        )),
+        labels: LabelSet::try_from_pairs(&[("method", "GET")]).unwrap(),
+        aggregation,
+        start_time: Timestamp::from_unix_nanos(0).unwrap(),
+        end_time: Timestamp::from_unix_nanos(0).unwrap(),
+        exemplars: Vec::new(),
+    };
+
+    let plain = StatsdExporter::new("127.0.0.1:8125", Format::Statsd).unwrap();
+    assert_eq!(
+        plain.encode(&record),
+        vec![
+            "latency.sum:30.25|c",
+            "latency.count:4|c",
+            "latency.bucket.le_0_5:1|c",
+            "latency.bucket.le_10:3|c",
+            "latency.bucket.le_Inf:4|c",
+        ]
+    );
+    let dog = StatsdExporter::new("127.0.0.1:8125", Format::DogStatsd).unwrap();
+    assert_eq!(
+        dog.encode(&record)[2..],
+        [
+            "latency.bucket:1|c|#le:0.5,method:GET",
+            "latency.bucket:3|c|#le:10,method:GET",
+            "latency.bucket:4|c|#le:+Inf,method:GET",
+        ]
+    );
+}
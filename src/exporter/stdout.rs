@@ -0,0 +1,238 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+
+use crate::api::trace::span_context::{SpanId, TraceId};
+use crate::api::trace::span_data::SpanData;
+use crate::exporter::json;
+use crate::sdk::trace::export::{ResultCode, SpanExporter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Indented trees, children grouped under their parent span. Spans no root leads to, e.g.
+    /// in a parent cycle, are printed as further roots.
+    Pretty,
+    /// One JSON object per line.
+    JsonLines,
+}
+
+/// Writes finished spans to any `io::Write`, for local debugging.
+pub struct StdoutExporter<W: Write = io::Stdout> {
+    writer: W,
+    format: Format,
+}
+
+impl<W: Write> StdoutExporter<W> {
+    pub fn new(writer: W, format: Format) -> Self {
+        Self { writer, format }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_json_lines(&mut self, batch: &[SpanData]) -> io::Result<()> {
+        for span in batch {
+            serde_json::to_writer(&mut self.writer, &json::encode_span(span))?;
+            self.writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    fn write_pretty(&mut self, batch: &[SpanData]) -> io::Result<()> {
+        let mut traces: Vec<&TraceId> = Vec::new();
+        for span in batch {
+            if !traces.contains(&span.trace_id()) {
+                traces.push(span.trace_id());
+            }
+        }
+
+        for trace_id in traces {
+            let spans: Vec<&SpanData> = batch.iter().filter(|x| x.trace_id() == trace_id).collect();
+            let mut children: HashMap<&SpanId, Vec<&SpanData>> = HashMap::new();
+            let mut roots = Vec::new();
+            for span in spans.iter() {
+                match span
+                    .parent_span_id()
+                    .filter(|p| spans.iter().any(|x| x.span_id() == *p))
+                {
+                    Some(p) => children.entry(p).or_default().push(span),
+                    None => roots.push(*span),
+                }
+            }
+
+            writeln!(self.writer, "trace {}", trace_id.to_base16())?;
+            roots.sort_by_key(|x| x.start_time().as_nanos());
+            let mut rest = spans.clone();
+            rest.sort_by_key(|x| x.start_time().as_nanos());
+            let mut written = HashSet::new();
+            for root in roots.into_iter().chain(rest) {
+                self.write_tree(root, &children, 1, &mut written)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_tree(
+        &mut self,
+        span: &SpanData,
+        children: &HashMap<&SpanId, Vec<&SpanData>>,
+        depth: usize,
+        written: &mut HashSet<*const SpanData>,
+    ) -> io::Result<()> {
+        if !written.insert(span) {
+            return Ok(());
+        }
+        let indent = "  ".repeat(depth);
+        let duration = span
+            .end_time()
+            .duration_since_as_micros(span.start_time())
+            .map(|x| format!("{}.{:03}ms", x / 1000, x % 1000))
+            .unwrap_or_else(|| "-".to_owned());
+        writeln!(
+            self.writer,
+            "{}{} [{}] {} {} {}",
+            indent,
+            span.name(),
+            json::encode_kind(span.kind()),
+            span.span_id().to_base16(),
+            duration,
+            span.status().canonical_code()
+        )?;
+
+        let mut attributes: Vec<_> = span.attributes().collect();
+        attributes.sort_by_key(|(k, _)| k.as_str());
+        for (k, v) in attributes {
+            writeln!(self.writer, "{}  {}={}", indent, k, v)?;
+        }
+        let dropped = [
+            ("attributes", span.dropped_attributes_count()),
+            ("events", span.dropped_events_count()),
+            ("links", span.dropped_links_count()),
+        ];
+        for (what, n) in dropped.iter().filter(|(_, n)| *n > 0) {
+            writeln!(self.writer, "{}  ({} {} dropped)", indent, n, what)?;
+        }
+        for event in span.events() {
+            let offset = event
+                .timestamp()
+                .duration_since_as_micros(span.start_time())
+                .unwrap_or(0);
+            writeln!(
+                self.writer,
+                "{}  @+{}.{:03}ms {}",
+                indent,
+                offset / 1000,
+                offset % 1000,
+                event.name()
+            )?;
+        }
+
+        if let Some(xs) = children.get(span.span_id()) {
+            let mut xs = xs.clone();
+            xs.sort_by_key(|x| x.start_time().as_nanos());
+            for x in xs {
+                self.write_tree(x, children, depth + 1, written)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for StdoutExporter<io::Stdout> {
+    fn default() -> Self {
+        Self::new(io::stdout(), Format::Pretty)
+    }
+}
+
+impl<W: Write> SpanExporter for StdoutExporter<W> {
+    fn export(&mut self, batch: &[SpanData]) -> ResultCode {
+        let written = match self.format {
+            Format::Pretty => self.write_pretty(batch),
+            Format::JsonLines => self.write_json_lines(batch),
+        };
+        match written.and_then(|_| self.writer.flush()) {
+            Ok(_) => ResultCode::Success,
+            Err(_) => ResultCode::FailedNotRetryable,
+        }
+    }
+
+    fn shutdown(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+#[test]
+fn stdout_pretty() {
+    use crate::api::resources::Resource;
+    use crate::api::trace::key::Value;
+    use crate::exporter::test_span_data;
+
+    let r = Resource::default();
+    let mut child = test_span_data(&r, "child", 2, Some(1), 1_500, 2_250);
+    child.attributes.insert(
+        "db.statement".to_owned(),
+        Value::String("SELECT 1".to_owned()),
+    );
+    let batch = [
+        child,
+        test_span_data(&r, "root", 1, None, 1_000, 3_000),
+        test_span_data(&r, "sibling", 3, Some(1), 2_500, 2_600),
+    ];
+
+    let mut exporter = StdoutExporter::new(Vec::new(), Format::Pretty);
+    assert_eq!(exporter.export(&batch), ResultCode::Success);
+    assert_eq!(
+        String::from_utf8(exporter.into_inner()).unwrap(),
+        "trace 0000000000000000000000000000002a\n\
+         \x20 root [INTERNAL] 0000000000000001 2.000ms Ok\n\
+         \x20   child [INTERNAL] 0000000000000002 0.750ms Ok\n\
+         \x20     db.statement=SELECT 1\n\
+         \x20   sibling [INTERNAL] 0000000000000003 0.100ms Ok\n"
+    );
+}
+
+#[test]
+fn stdout_pretty_prints_cycles_as_roots() {
+    use crate::api::resources::Resource;
+    use crate::exporter::test_span_data;
+
+    let r = Resource::default();
+    let batch = [
+        test_span_data(&r, "a", 1, Some(2), 1_000, 2_000),
+        test_span_data(&r, "b", 2, Some(1), 1_500, 2_000),
+        test_span_data(&r, "self", 3, Some(3), 3_000, 4_000),
+    ];
+
+    let mut exporter = StdoutExporter::new(Vec::new(), Format::Pretty);
+    assert_eq!(exporter.export(&batch), ResultCode::Success);
+    assert_eq!(
+        String::from_utf8(exporter.into_inner()).unwrap(),
+        "trace 0000000000000000000000000000002a\n\
+         \x20 a [INTERNAL] 0000000000000001 1.000ms Ok\n\
+         \x20   b [INTERNAL] 0000000000000002 0.500ms Ok\n\
+         \x20 self [INTERNAL] 0000000000000003 1.000ms Ok\n"
+    );
+}
+
+#[test]
+fn stdout_json_lines() {
+    use crate::api::resources::Resource;
+    use crate::exporter::test_span_data;
+
+    let r = Resource::default();
+    let batch = [
+        test_span_data(&r, "a", 1, None, 1, 2),
+        test_span_data(&r, "b", 2, Some(1), 1, 2),
+    ];
+
+    let mut exporter = StdoutExporter::new(Vec::new(), Format::JsonLines);
+    assert_eq!(exporter.export(&batch), ResultCode::Success);
+    let out = String::from_utf8(exporter.into_inner()).unwrap();
+    let lines: Vec<serde_json::Value> = out
+        .lines()
+        .map(|x| serde_json::from_str(x).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["name"], "a");
+    assert_eq!(lines[1]["parentSpanId"], "0000000000000001");
+}
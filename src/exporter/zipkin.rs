@@ -0,0 +1,285 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use serde_json::{json, Map, Value as Json};
+
+use crate::api::semconv::resource::service;
+use crate::api::semconv::trace::net;
+use crate::api::trace::key::Value;
+use crate::api::trace::span_data::SpanData;
+use crate::api::trace::{SpanKind, TimedEvent};
+use crate::exporter::http::{self, Url};
+use crate::exporter::json;
+use crate::sdk::trace::export::{ResultCode, SpanExporter};
+
+const DEFAULT_ENDPOINT: &str = "http://localhost:9411/api/v2/spans";
+const DEFAULT_SERVICE_NAME: &str = "unknown_service";
+const STATUS_CODE: &str = "otel.status_code";
+const STATUS_DESCRIPTION: &str = "otel.status_description";
+const ERROR: &str = "error";
+const DROPPED_ATTRIBUTES_COUNT: &str = "otel.dropped_attributes_count";
+const DROPPED_EVENTS_COUNT: &str = "otel.dropped_events_count";
+const DROPPED_LINKS_COUNT: &str = "otel.dropped_links_count";
+
+/// Exports spans to a Zipkin collector as [Zipkin v2 JSON](https://zipkin.io/zipkin-api/#/default/post_spans).
+pub struct ZipkinExporter {
+    endpoint: Url,
+    service_name: Option<String>,
+    timeout: Duration,
+}
+
+impl ZipkinExporter {
+    /// `endpoint` must be a plain `http://` url, e.g. `http://localhost:9411/api/v2/spans`.
+    pub fn new(endpoint: &str) -> std::io::Result<Self> {
+        Url::parse(endpoint).map(|endpoint| Self {
+            endpoint,
+            service_name: None,
+            timeout: Duration::from_secs(10),
+        })
+    }
+
+    /// Overrides the `service.name` label of each span's `Resource`.
+    pub fn with_service_name(self, service_name: &str) -> Self {
+        Self {
+            service_name: Some(service_name.to_owned()),
+            ..self
+        }
+    }
+
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    pub fn encode(&self, batch: &[SpanData]) -> Json {
+        Json::Array(batch.iter().map(|x| self.encode_span(x)).collect())
+    }
+
+    fn encode_span(&self, span: &SpanData) -> Json {
+        let mut o = Map::new();
+        o.insert("traceId".to_owned(), json!(span.trace_id().to_base16()));
+        o.insert("id".to_owned(), json!(span.span_id().to_base16()));
+        if let Some(p) = span.parent_span_id() {
+            o.insert("parentId".to_owned(), json!(p.to_base16()));
+        }
+        o.insert("name".to_owned(), json!(span.name()));
+        if let Some(k) = encode_kind(span.kind()) {
+            o.insert("kind".to_owned(), json!(k));
+        }
+        o.insert(
+            "timestamp".to_owned(),
+            json!(span.start_time().as_micros().unwrap_or(0) as u64),
+        );
+        if let Some(d) = span.end_time().duration_since_as_micros(span.start_time()) {
+            o.insert("duration".to_owned(), json!(d as u64));
+        }
+        o.insert("localEndpoint".to_owned(), self.encode_endpoint(span));
+        let annotations: Vec<Json> = span.events().map(encode_annotation).collect();
+        if !annotations.is_empty() {
+            o.insert("annotations".to_owned(), Json::Array(annotations));
+        }
+        o.insert("tags".to_owned(), Json::Object(encode_tags(span)));
+
+        Json::Object(o)
+    }
+
+    /// The service from the resource, and the address from the span's `net.host.ip`.
+    fn encode_endpoint(&self, span: &SpanData) -> Json {
+        let service_name = self
+            .service_name
+            .as_deref()
+            .or_else(|| {
+                span.resource()
+                    .get(service::NAME.name())
+                    .and_then(|x| x.as_str())
+            })
+            .unwrap_or(DEFAULT_SERVICE_NAME);
+
+        let mut o = Map::new();
+        o.insert("serviceName".to_owned(), json!(service_name));
+        let ip = match span.attributes.get(net::HOST_IP.name()) {
+            Some(Value::String(x)) => x.parse().ok(),
+            _ => None,
+        };
+        match ip {
+            Some(IpAddr::V4(ip)) => {
+                o.insert("ipv4".to_owned(), json!(ip.to_string()));
+            }
+            Some(IpAddr::V6(ip)) => {
+                o.insert("ipv6".to_owned(), json!(ip.to_string()));
+            }
+            None => {}
+        }
+        Json::Object(o)
+    }
+}
+
+impl SpanExporter for ZipkinExporter {
+    fn export(&mut self, batch: &[SpanData]) -> ResultCode {
+        let body = match serde_json::to_vec(&self.encode(batch)) {
+            Ok(x) => x,
+            Err(_) => return ResultCode::FailedNotRetryable,
+        };
+
+        match http::post(&self.endpoint, "application/json", &body, self.timeout) {
+            Ok(200..=299) => ResultCode::Success,
+            Ok(400..=499) => ResultCode::FailedNotRetryable,
+            _ => ResultCode::FailedRetryable,
+        }
+    }
+
+    fn shutdown(&mut self) {}
+}
+
+impl Default for ZipkinExporter {
+    fn default() -> Self {
+        Self::new(DEFAULT_ENDPOINT).unwrap()
+    }
+}
+
+fn encode_kind(kind: SpanKind) -> Option<&'static str> {
+    match kind {
+        SpanKind::INTERNAL => None,
+        _ => Some(json::encode_kind(kind)),
+    }
+}
+
+fn encode_annotation(event: &TimedEvent) -> Json {
+    json!({
+        "timestamp": event.timestamp().as_micros().unwrap_or(0) as u64,
+        "value": event.name(),
+    })
+}
+
+fn encode_tags(span: &SpanData) -> Map<String, Json> {
+    let mut o: Map<String, Json> = span
+        .attributes()
+        .map(|(k, v)| (k.to_owned(), json!(v.to_string())))
+        .collect();
+
+    let status = span.status();
+    o.insert(
+        STATUS_CODE.to_owned(),
+        json!(status.canonical_code().to_string()),
+    );
+    if let Some(d) = status.description() {
+        o.insert(STATUS_DESCRIPTION.to_owned(), json!(d));
+    }
+    if !status.is_ok() {
+        o.insert(
+            ERROR.to_owned(),
+            json!(status.description().unwrap_or_default()),
+        );
+    }
+    for (k, v) in [
+        (DROPPED_ATTRIBUTES_COUNT, span.dropped_attributes_count()),
+        (DROPPED_EVENTS_COUNT, span.dropped_events_count()),
+        (DROPPED_LINKS_COUNT, span.dropped_links_count()),
+    ]
+    .iter()
+    {
+        if *v > 0 {
+            o.insert((*k).to_owned(), json!(v.to_string()));
+        }
+    }
+    o
+}
+
+#[test]
+fn zipkin_encode() {
+    use crate::api::resources::Resource;
+    use crate::api::trace::key::Value;
+    use crate::api::trace::status::Status;
+    use crate::api::trace::{Event, Timestamp};
+    use crate::exporter::test_span_data;
+    use std::time::UNIX_EPOCH;
+
+    let mut r = Resource::default();
+    r.try_upsert("service.name", "frontend").unwrap();
+    let mut s = test_span_data(&r, "get", 2, Some(1), 1_000, 3_000);
+    s.kind = SpanKind::CLIENT;
+    s.attributes.insert(
+        "net.host.ip".to_owned(),
+        Value::String("10.0.0.1".to_owned()),
+    );
+    s.attributes
+        .insert("http.method".to_owned(), Value::String("GET".to_owned()));
+    s.attributes
+        .insert("http.status_code".to_owned(), Value::Int64(500));
+    s.events.push(TimedEvent::new_with_timestamp(
+        Timestamp(UNIX_EPOCH + Duration::from_micros(1_500)),
+        Event::new("retry"),
+    ));
+    s.status = Status::internal().with_description("boom".to_owned());
+    s.dropped_links_count = 2;
+
+    let a = ZipkinExporter::default().encode(&[s]);
+    let e = json!([{
+        "traceId": "0000000000000000000000000000002a",
+        "id": "0000000000000002",
+        "parentId": "0000000000000001",
+        "name": "get",
+        "kind": "CLIENT",
+        "timestamp": 1_000,
+        "duration": 2_000,
+        "localEndpoint": {"serviceName": "frontend", "ipv4": "10.0.0.1"},
+        "annotations": [{"timestamp": 1_500, "value": "retry"}],
+        "tags": {
+            "http.method": "GET",
+            "http.status_code": "500",
+            "net.host.ip": "10.0.0.1",
+            "otel.status_code": "Internal",
+            "otel.status_description": "boom",
+            "error": "boom",
+            "otel.dropped_links_count": "2",
+        },
+    }]);
+    assert_eq!(a, e);
+}
+
+#[test]
+fn zipkin_export() {
+    use crate::api::resources::Resource;
+    use crate::exporter::test_span_data;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/api/v2/spans", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(x) = line.strip_prefix("Content-Length: ") {
+                content_length = x.trim().parse().unwrap();
+            }
+            request.push_str(&line);
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        request.push_str(&String::from_utf8(body).unwrap());
+        stream
+            .write_all(b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        request
+    });
+
+    let r = Resource::default();
+    let mut exporter = ZipkinExporter::new(&url)
+        .unwrap()
+        .with_service_name("backend");
+    let s = test_span_data(&r, "get", 2, Some(1), 1_000, 3_000);
+    assert_eq!(exporter.export(&[s]), ResultCode::Success);
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /api/v2/spans HTTP/1.1\r\n"));
+    assert!(request.contains("Content-Type: application/json\r\n"));
+    assert!(request.contains(r#""serviceName":"backend""#));
+}
@@ -1 +1,3 @@
 pub mod api;
+pub mod exporter;
+pub mod sdk;
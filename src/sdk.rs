@@ -0,0 +1,4 @@
+pub mod config;
+pub mod metrics;
+pub mod resource;
+pub mod trace;
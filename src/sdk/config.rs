@@ -0,0 +1,604 @@
+//! SDK configuration from the [standard environment variables](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/sdk-environment-variables.md).
+//!
+//! Unset or empty variables take their default. Invalid or unsupported values are also replaced
+//! by the default, and reported by `EnvConfig::warnings`.
+
+use std::env;
+use std::io;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::api::context::{HttpTextExtract, HttpTextInject};
+use crate::api::resources::Resource;
+use crate::api::trace::span_context::SpanContext;
+use crate::api::trace::span_limits::SpanLimits;
+use crate::api::trace::trace_context::TraceContext;
+use crate::exporter::stdout::StdoutExporter;
+use crate::exporter::zipkin::ZipkinExporter;
+use crate::sdk::trace::export::SpanExporter;
+use crate::sdk::trace::processor::{BatchConfig, BatchSpanProcessor};
+use crate::sdk::trace::provider::TracerProvider;
+use crate::sdk::trace::sampler::Sampler;
+
+const OTEL_SDK_DISABLED: &str = "OTEL_SDK_DISABLED";
+const OTEL_TRACES_SAMPLER: &str = "OTEL_TRACES_SAMPLER";
+const OTEL_TRACES_SAMPLER_ARG: &str = "OTEL_TRACES_SAMPLER_ARG";
+const OTEL_PROPAGATORS: &str = "OTEL_PROPAGATORS";
+const OTEL_TRACES_EXPORTER: &str = "OTEL_TRACES_EXPORTER";
+const OTEL_EXPORTER_ZIPKIN_ENDPOINT: &str = "OTEL_EXPORTER_ZIPKIN_ENDPOINT";
+const OTEL_EXPORTER_ZIPKIN_TIMEOUT: &str = "OTEL_EXPORTER_ZIPKIN_TIMEOUT";
+const OTEL_BSP_SCHEDULE_DELAY: &str = "OTEL_BSP_SCHEDULE_DELAY";
+const OTEL_BSP_EXPORT_TIMEOUT: &str = "OTEL_BSP_EXPORT_TIMEOUT";
+const OTEL_BSP_MAX_QUEUE_SIZE: &str = "OTEL_BSP_MAX_QUEUE_SIZE";
+const OTEL_BSP_MAX_EXPORT_BATCH_SIZE: &str = "OTEL_BSP_MAX_EXPORT_BATCH_SIZE";
+const OTEL_ATTRIBUTE_COUNT_LIMIT: &str = "OTEL_ATTRIBUTE_COUNT_LIMIT";
+const OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT: &str = "OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT";
+const OTEL_SPAN_EVENT_COUNT_LIMIT: &str = "OTEL_SPAN_EVENT_COUNT_LIMIT";
+const OTEL_SPAN_LINK_COUNT_LIMIT: &str = "OTEL_SPAN_LINK_COUNT_LIMIT";
+const OTEL_EVENT_ATTRIBUTE_COUNT_LIMIT: &str = "OTEL_EVENT_ATTRIBUTE_COUNT_LIMIT";
+const OTEL_LINK_ATTRIBUTE_COUNT_LIMIT: &str = "OTEL_LINK_ATTRIBUTE_COUNT_LIMIT";
+const OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT: &str = "OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT";
+
+const DEFAULT_ZIPKIN_ENDPOINT: &str = "http://localhost:9411/api/v2/spans";
+const DEFAULT_ZIPKIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Propagator {
+    /// [W3C Trace Context](https://www.w3.org/TR/trace-context/) `traceparent` and `tracestate` headers.
+    TraceContext,
+}
+
+impl Propagator {
+    pub fn inject<C, R>(
+        &self,
+        context: &SpanContext,
+        carrier: &mut C,
+        setter: fn(&mut C, String, String) -> R,
+    ) {
+        match self {
+            Propagator::TraceContext => context.inject(carrier, setter),
+        }
+    }
+
+    pub fn extract<C>(
+        &self,
+        carrier: &C,
+        getter: for<'r> fn(&'r C, &str) -> Option<&'r String>,
+    ) -> Option<TraceContext> {
+        match self {
+            Propagator::TraceContext => TraceContext::extract(carrier, getter),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Exporter {
+    /// Pretty printed to stdout.
+    Console,
+    Zipkin {
+        endpoint: String,
+        timeout: Duration,
+    },
+}
+
+impl Exporter {
+    /// A new exporter of this kind; fails on an invalid Zipkin endpoint.
+    pub fn build(&self) -> io::Result<Box<dyn SpanExporter + Send>> {
+        Ok(match self {
+            Exporter::Console => Box::new(StdoutExporter::default()),
+            Exporter::Zipkin { endpoint, timeout } => {
+                Box::new(ZipkinExporter::new(endpoint)?.with_timeout(*timeout))
+            }
+        })
+    }
+}
+
+/// Tracer provider settings read from `OTEL_*` environment variables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvConfig {
+    disabled: bool,
+    sampler: Sampler,
+    propagators: Vec<Propagator>,
+    exporters: Vec<Exporter>,
+    batch: BatchConfig,
+    limits: SpanLimits,
+    warnings: Vec<String>,
+}
+
+impl EnvConfig {
+    pub fn from_env() -> Self {
+        Self::from_vars(|x| env::var(x).ok())
+    }
+
+    /// Reads the variables through `env`, e.g. to override some from the command line.
+    pub fn from_vars(env: impl Fn(&str) -> Option<String>) -> Self {
+        let mut vars = Vars {
+            env: &env,
+            warnings: Vec::new(),
+        };
+        let disabled = vars.parse_disabled();
+        let sampler = vars.parse_sampler();
+        let propagators = vars.parse_propagators();
+        let exporters = vars.parse_exporters();
+        let batch = vars.parse_batch();
+        let limits = vars.parse_limits();
+        Self {
+            disabled,
+            sampler,
+            propagators,
+            exporters,
+            batch,
+            limits,
+            warnings: vars.warnings,
+        }
+    }
+
+    /// `OTEL_SDK_DISABLED=true`: nothing is sampled or exported.
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    pub fn sampler(&self) -> &Sampler {
+        &self.sampler
+    }
+
+    pub fn propagators(&self) -> &[Propagator] {
+        &self.propagators
+    }
+
+    /// Injects `context` with every configured propagator, so nothing under
+    /// `OTEL_PROPAGATORS=none`.
+    pub fn inject<C, R>(
+        &self,
+        context: &SpanContext,
+        carrier: &mut C,
+        setter: fn(&mut C, String, String) -> R,
+    ) {
+        for propagator in &self.propagators {
+            propagator.inject(context, carrier, setter);
+        }
+    }
+
+    /// The context found by the first configured propagator that finds one.
+    pub fn extract<C>(
+        &self,
+        carrier: &C,
+        getter: for<'r> fn(&'r C, &str) -> Option<&'r String>,
+    ) -> Option<TraceContext> {
+        self.propagators
+            .iter()
+            .find_map(|x| x.extract(carrier, getter))
+    }
+
+    pub fn exporters(&self) -> &[Exporter] {
+        &self.exporters
+    }
+
+    pub fn batch(&self) -> &BatchConfig {
+        &self.batch
+    }
+
+    pub fn limits(&self) -> &SpanLimits {
+        &self.limits
+    }
+
+    /// One message per variable whose value was ignored, naming the variable.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// A provider exporting through one `BatchSpanProcessor` per configured exporter; fails if
+    /// one of them cannot be built.
+    pub fn tracer_provider(&self, resource: Resource) -> io::Result<TracerProvider> {
+        let provider = TracerProvider::new(resource).with_limits(self.limits);
+        if self.disabled {
+            return Ok(provider.with_sampler(Sampler::AlwaysOff));
+        }
+        let mut provider = provider.with_sampler(self.sampler.clone());
+        for exporter in &self.exporters {
+            let exporter = exporter.build()?;
+            provider =
+                provider.with_processor(Box::new(BatchSpanProcessor::new(exporter, self.batch)));
+        }
+        Ok(provider)
+    }
+}
+
+struct Vars<'a, F> {
+    env: &'a F,
+    warnings: Vec<String>,
+}
+
+impl<'a, F: Fn(&str) -> Option<String>> Vars<'a, F> {
+    /// Trimmed value; empty values count as unset.
+    fn get(&self, name: &str) -> Option<String> {
+        (self.env)(name)
+            .map(|x| x.trim().to_owned())
+            .filter(|x| !x.is_empty())
+    }
+
+    fn warn(&mut self, name: &str, message: String) {
+        self.warnings.push(format!("{}: {}", name, message));
+    }
+
+    fn parse<T: FromStr>(&mut self, name: &str, valid: impl Fn(&T) -> bool) -> Option<T> {
+        let value = self.get(name)?;
+        match value.parse().ok().filter(|x| valid(x)) {
+            Some(x) => Some(x),
+            None => {
+                self.warn(
+                    name,
+                    format!("invalid value `{}`, using the default", value),
+                );
+                None
+            }
+        }
+    }
+
+    fn millis(&mut self, name: &str) -> Option<Duration> {
+        self.parse(name, |_| true).map(Duration::from_millis)
+    }
+
+    fn parse_disabled(&mut self) -> bool {
+        match self.get(OTEL_SDK_DISABLED).map(|x| x.to_lowercase()) {
+            Some(x) if x == "true" => true,
+            Some(x) if x == "false" => false,
+            Some(x) => {
+                self.warn(
+                    OTEL_SDK_DISABLED,
+                    format!("invalid value `{}`, using `false`", x),
+                );
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn parse_sampler(&mut self) -> Sampler {
+        let name = match self.get(OTEL_TRACES_SAMPLER) {
+            Some(x) => x,
+            None => return Sampler::default(),
+        };
+        let ratio = |vars: &mut Self| {
+            let ratio = vars.parse(OTEL_TRACES_SAMPLER_ARG, |x: &f64| (0.0..=1.0).contains(x));
+            Sampler::TraceIdRatio(ratio.unwrap_or(1.0))
+        };
+        let parent_based = |x| Sampler::ParentBased(Box::new(x));
+        match name.as_str() {
+            "always_on" => Sampler::AlwaysOn,
+            "always_off" => Sampler::AlwaysOff,
+            "traceidratio" => ratio(self),
+            "parentbased_always_on" => parent_based(Sampler::AlwaysOn),
+            "parentbased_always_off" => parent_based(Sampler::AlwaysOff),
+            "parentbased_traceidratio" => parent_based(ratio(self)),
+            "jaeger_remote" | "parentbased_jaeger_remote" | "xray" => {
+                self.warn(
+                    OTEL_TRACES_SAMPLER,
+                    format!(
+                        "unsupported sampler `{}`, using `parentbased_always_on`",
+                        name
+                    ),
+                );
+                Sampler::default()
+            }
+            _ => {
+                self.warn(
+                    OTEL_TRACES_SAMPLER,
+                    format!("unknown sampler `{}`, using `parentbased_always_on`", name),
+                );
+                Sampler::default()
+            }
+        }
+    }
+
+    fn parse_propagators(&mut self) -> Vec<Propagator> {
+        let value = match self.get(OTEL_PROPAGATORS) {
+            Some(x) => x,
+            None => return vec![Propagator::TraceContext],
+        };
+        let mut propagators = Vec::new();
+        for name in value.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            let propagator = match name {
+                "none" => return Vec::new(),
+                "tracecontext" => Propagator::TraceContext,
+                "baggage" | "b3" | "b3multi" | "jaeger" | "xray" | "ottrace" => {
+                    self.warn(
+                        OTEL_PROPAGATORS,
+                        format!("unsupported propagator `{}` skipped", name),
+                    );
+                    continue;
+                }
+                _ => {
+                    self.warn(
+                        OTEL_PROPAGATORS,
+                        format!("unknown propagator `{}` skipped", name),
+                    );
+                    continue;
+                }
+            };
+            if !propagators.contains(&propagator) {
+                propagators.push(propagator);
+            }
+        }
+        propagators
+    }
+
+    fn parse_exporters(&mut self) -> Vec<Exporter> {
+        let value = match self.get(OTEL_TRACES_EXPORTER) {
+            Some(x) => x,
+            None => return Vec::new(),
+        };
+        let mut exporters = Vec::new();
+        for name in value.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            let exporter = match name {
+                "none" => return Vec::new(),
+                "console" | "logging" => Exporter::Console,
+                "zipkin" => self.parse_zipkin(),
+                "otlp" | "jaeger" => {
+                    self.warn(
+                        OTEL_TRACES_EXPORTER,
+                        format!("unsupported exporter `{}` skipped", name),
+                    );
+                    continue;
+                }
+                _ => {
+                    self.warn(
+                        OTEL_TRACES_EXPORTER,
+                        format!("unknown exporter `{}` skipped", name),
+                    );
+                    continue;
+                }
+            };
+            if !exporters.contains(&exporter) {
+                exporters.push(exporter);
+            }
+        }
+        exporters
+    }
+
+    fn parse_zipkin(&mut self) -> Exporter {
+        let endpoint = match self.get(OTEL_EXPORTER_ZIPKIN_ENDPOINT) {
+            Some(x) if ZipkinExporter::new(&x).is_ok() => x,
+            Some(x) => {
+                self.warn(
+                    OTEL_EXPORTER_ZIPKIN_ENDPOINT,
+                    format!("invalid endpoint `{}`, using the default", x),
+                );
+                DEFAULT_ZIPKIN_ENDPOINT.to_owned()
+            }
+            None => DEFAULT_ZIPKIN_ENDPOINT.to_owned(),
+        };
+        let timeout = self
+            .millis(OTEL_EXPORTER_ZIPKIN_TIMEOUT)
+            .unwrap_or(DEFAULT_ZIPKIN_TIMEOUT);
+        Exporter::Zipkin { endpoint, timeout }
+    }
+
+    fn parse_batch(&mut self) -> BatchConfig {
+        let default = BatchConfig::default();
+        let positive = |x: &usize| *x > 0;
+        let mut batch = BatchConfig {
+            schedule_delay: self
+                .millis(OTEL_BSP_SCHEDULE_DELAY)
+                .unwrap_or(default.schedule_delay),
+            max_queue_size: self
+                .parse(OTEL_BSP_MAX_QUEUE_SIZE, positive)
+                .unwrap_or(default.max_queue_size),
+            max_export_batch_size: self
+                .parse(OTEL_BSP_MAX_EXPORT_BATCH_SIZE, positive)
+                .unwrap_or(default.max_export_batch_size),
+        };
+        if batch.max_export_batch_size > batch.max_queue_size {
+            self.warn(
+                OTEL_BSP_MAX_EXPORT_BATCH_SIZE,
+                format!(
+                    "larger than {} {}, using that instead",
+                    OTEL_BSP_MAX_QUEUE_SIZE, batch.max_queue_size
+                ),
+            );
+            batch.max_export_batch_size = batch.max_queue_size;
+        }
+        if let Some(x) = self.get(OTEL_BSP_EXPORT_TIMEOUT) {
+            self.warn(
+                OTEL_BSP_EXPORT_TIMEOUT,
+                format!("`{}` ignored, exporters use their own timeouts", x),
+            );
+        }
+        batch
+    }
+
+    fn parse_limits(&mut self) -> SpanLimits {
+        let default = SpanLimits::default();
+        let mut count = |name| self.parse(name, |_: &usize| true);
+        let attributes = count(OTEL_ATTRIBUTE_COUNT_LIMIT);
+        SpanLimits {
+            max_attributes: count(OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT)
+                .or(attributes)
+                .unwrap_or(default.max_attributes),
+            max_events: count(OTEL_SPAN_EVENT_COUNT_LIMIT).unwrap_or(default.max_events),
+            max_links: count(OTEL_SPAN_LINK_COUNT_LIMIT).unwrap_or(default.max_links),
+            max_attributes_per_event: count(OTEL_EVENT_ATTRIBUTE_COUNT_LIMIT)
+                .or(attributes)
+                .unwrap_or(default.max_attributes_per_event),
+            max_attributes_per_link: count(OTEL_LINK_ATTRIBUTE_COUNT_LIMIT)
+                .or(attributes)
+                .unwrap_or(default.max_attributes_per_link),
+            max_attribute_value_length: count(OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT)
+                .or(default.max_attribute_value_length),
+        }
+    }
+}
+
+#[test]
+fn defaults_without_variables() {
+    let config = EnvConfig::from_vars(|_| None);
+    assert!(!config.is_disabled());
+    assert_eq!(config.sampler(), &Sampler::default());
+    assert_eq!(config.propagators(), &[Propagator::TraceContext]);
+    assert!(config.exporters().is_empty());
+    assert_eq!(config.batch(), &BatchConfig::default());
+    assert_eq!(config.limits(), &SpanLimits::default());
+    assert!(config.warnings().is_empty());
+}
+
+#[test]
+fn parses_variables() {
+    let env = |x: &str| {
+        let value = match x {
+            "OTEL_TRACES_SAMPLER" => "parentbased_traceidratio",
+            "OTEL_TRACES_SAMPLER_ARG" => "0.25",
+            "OTEL_PROPAGATORS" => "tracecontext, tracecontext",
+            "OTEL_TRACES_EXPORTER" => "zipkin,console",
+            "OTEL_EXPORTER_ZIPKIN_ENDPOINT" => "http://zipkin:9411/api/v2/spans",
+            "OTEL_EXPORTER_ZIPKIN_TIMEOUT" => "500",
+            "OTEL_BSP_SCHEDULE_DELAY" => "100",
+            "OTEL_BSP_MAX_QUEUE_SIZE" => "10",
+            "OTEL_BSP_MAX_EXPORT_BATCH_SIZE" => "5",
+            "OTEL_ATTRIBUTE_COUNT_LIMIT" => "16",
+            "OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT" => "32",
+            "OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT" => "64",
+            "OTEL_SDK_DISABLED" => "",
+            _ => return None,
+        };
+        Some(value.to_owned())
+    };
+    let config = EnvConfig::from_vars(env);
+    assert_eq!(config.warnings(), &[] as &[String]);
+    assert_eq!(
+        config.sampler(),
+        &Sampler::ParentBased(Box::new(Sampler::TraceIdRatio(0.25)))
+    );
+    assert_eq!(config.propagators(), &[Propagator::TraceContext]);
+    assert_eq!(
+        config.exporters(),
+        &[
+            Exporter::Zipkin {
+                endpoint: "http://zipkin:9411/api/v2/spans".to_owned(),
+                timeout: Duration::from_millis(500)
+            },
+            Exporter::Console
+        ]
+    );
+    assert_eq!(
+        config.batch(),
+        &BatchConfig {
+            schedule_delay: Duration::from_millis(100),
+            max_queue_size: 10,
+            max_export_batch_size: 5
+        }
+    );
+    let limits = config.limits();
+    assert_eq!(limits.max_attributes, 32);
+    assert_eq!(limits.max_attributes_per_event, 16);
+    assert_eq!(limits.max_events, 128);
+    assert_eq!(limits.max_attribute_value_length, Some(64));
+}
+
+#[test]
+fn warns_about_invalid_values() {
+    let env = |x: &str| {
+        let value = match x {
+            "OTEL_SDK_DISABLED" => "yes",
+            "OTEL_TRACES_SAMPLER" => "traceidratio",
+            "OTEL_TRACES_SAMPLER_ARG" => "1.5",
+            "OTEL_PROPAGATORS" => "b3,foo",
+            "OTEL_TRACES_EXPORTER" => "otlp,zipkin",
+            "OTEL_EXPORTER_ZIPKIN_ENDPOINT" => "https://zipkin",
+            "OTEL_BSP_MAX_QUEUE_SIZE" => "0",
+            "OTEL_BSP_MAX_EXPORT_BATCH_SIZE" => "4096",
+            "OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT" => "-1",
+            _ => return None,
+        };
+        Some(value.to_owned())
+    };
+    let config = EnvConfig::from_vars(env);
+    assert!(!config.is_disabled());
+    assert_eq!(config.sampler(), &Sampler::TraceIdRatio(1.0));
+    assert!(config.propagators().is_empty());
+    assert_eq!(
+        config.exporters(),
+        &[Exporter::Zipkin {
+            endpoint: DEFAULT_ZIPKIN_ENDPOINT.to_owned(),
+            timeout: DEFAULT_ZIPKIN_TIMEOUT
+        }]
+    );
+    assert_eq!(config.batch().max_queue_size, 2048);
+    assert_eq!(config.batch().max_export_batch_size, 2048);
+    assert_eq!(config.limits().max_attributes, 128);
+    let vars: Vec<&str> = config
+        .warnings()
+        .iter()
+        .map(|x| x.split(':').next().unwrap())
+        .collect();
+    assert_eq!(
+        vars,
+        vec![
+            OTEL_SDK_DISABLED,
+            OTEL_TRACES_SAMPLER_ARG,
+            OTEL_PROPAGATORS,
+            OTEL_PROPAGATORS,
+            OTEL_TRACES_EXPORTER,
+            OTEL_EXPORTER_ZIPKIN_ENDPOINT,
+            OTEL_BSP_MAX_QUEUE_SIZE,
+            OTEL_BSP_MAX_EXPORT_BATCH_SIZE,
+            OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT,
+        ]
+    );
+}
+
+#[test]
+fn configured_propagators_inject_and_extract() {
+    use std::collections::HashMap;
+    use std::num::{NonZeroU128, NonZeroU64};
+
+    use crate::api::trace::span_context::{SpanId, TraceId, TraceOption, TraceState};
+
+    let t = TraceId::new(NonZeroU128::new(42).unwrap());
+    let context = SpanContext::new(
+        &t,
+        SpanId::new(NonZeroU64::new(7).unwrap()),
+        TraceOption::MASK_SAMPLE,
+        TraceState::empty(),
+    );
+
+    let config = EnvConfig::from_vars(|_| None);
+    let mut headers: HashMap<String, String> = HashMap::new();
+    config.inject(&context, &mut headers, HashMap::insert);
+    let extracted = config.extract(&headers, HashMap::get).unwrap();
+    assert_eq!(&extracted.trace_id, context.trace_id);
+    assert_eq!(extracted.span_id, context.span_id);
+
+    let none = EnvConfig::from_vars(|x| match x {
+        "OTEL_PROPAGATORS" => Some("none".to_owned()),
+        _ => None,
+    });
+    let mut empty: HashMap<String, String> = HashMap::new();
+    none.inject(&context, &mut empty, HashMap::insert);
+    assert!(empty.is_empty());
+    assert!(none.extract(&headers, HashMap::get).is_none());
+}
+
+#[test]
+fn disabled_provider_samples_nothing() {
+    let env = |x: &str| match x {
+        "OTEL_SDK_DISABLED" => Some("TRUE".to_owned()),
+        "OTEL_TRACES_EXPORTER" => Some("console".to_owned()),
+        _ => None,
+    };
+    let config = EnvConfig::from_vars(env);
+    assert!(config.is_disabled());
+    let provider = config.tracer_provider(Resource::default()).unwrap();
+    assert_eq!(provider.sampler(), &Sampler::AlwaysOff);
+    assert!(provider.tracer().new_root_context().trace_option.is_empty());
+}
+
+#[test]
+fn provider_fails_on_an_unbuildable_exporter() {
+    // Endpoints read from the environment are checked while parsing, so set one directly.
+    let mut config = EnvConfig::from_vars(|_| None);
+    config.exporters.push(Exporter::Zipkin {
+        endpoint: "not a url".to_owned(),
+        timeout: DEFAULT_ZIPKIN_TIMEOUT,
+    });
+    assert!(config.tracer_provider(Resource::default()).is_err());
+}
@@ -0,0 +1,452 @@
+//! [Metrics SDK](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/sdk-metrics.md)
+//!
+//! An `Accumulator` aggregates measurements per instrument and label set, and a
+//! `PeriodicCollector` snapshots it on an interval and hands the records to a
+//! `MetricExporter`.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::api::metrics::instrument::ObserverResult;
+use crate::api::metrics::labels::LabelSet;
+use crate::api::metrics::{Descriptor, Meter, MeterCore, ObserverCallback};
+use crate::api::trace::clock::{Clock, SystemClock};
+use crate::api::trace::span_context::{SpanId, TraceId};
+use crate::api::trace::Timestamp;
+use crate::sdk::metrics::aggregator::{Aggregation, AggregatorSelector, SimpleSelector};
+use crate::sdk::metrics::exemplar::{Exemplar, ExemplarReservoir};
+use crate::sdk::metrics::export::{MetricExporter, Record, Temporality};
+use crate::sdk::trace::export::ResultCode;
+
+pub mod aggregator;
+pub mod exemplar;
+pub mod export;
+
+type Key = (Arc<Descriptor>, LabelSet);
+type State = HashMap<Key, (Aggregation, ExemplarReservoir)>;
+type Observers = Vec<(
+    Arc<Descriptor>,
+    Arc<dyn Fn(&mut ObserverResult) + Send + Sync>,
+)>;
+
+/// `MeterCore` which aggregates measurements until the next `collect`.
+pub struct Accumulator {
+    selector: Box<dyn AggregatorSelector>,
+    clock: Arc<dyn Clock>,
+    label_keys: Option<Vec<String>>,
+    current: Mutex<(Timestamp, State)>,
+    observers: Mutex<Observers>,
+}
+
+impl Accumulator {
+    pub fn new() -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock::new());
+        Self {
+            selector: Box::new(SimpleSelector::new()),
+            current: Mutex::new((clock.now(), HashMap::new())),
+            clock,
+            label_keys: None,
+            observers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn with_selector(self, selector: Box<dyn AggregatorSelector>) -> Self {
+        Self { selector, ..self }
+    }
+
+    /// Replaces the system clock and restarts the current interval on it.
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            current: Mutex::new((clock.now(), HashMap::new())),
+            clock,
+            ..self
+        }
+    }
+
+    /// Aggregates by these labels only; the others are kept on exemplars.
+    pub fn with_label_keys(self, keys: &[&str]) -> Self {
+        Self {
+            label_keys: Some(keys.iter().map(|x| (*x).to_owned()).collect()),
+            ..self
+        }
+    }
+
+    /// Labels of the time series and the filtered ones.
+    fn split_labels(&self, labels: &LabelSet) -> (LabelSet, LabelSet) {
+        match &self.label_keys {
+            None => (labels.clone(), LabelSet::default()),
+            Some(keys) => {
+                let (kept, filtered): (Vec<_>, Vec<_>) = labels
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .partition(|(k, _)| keys.iter().any(|x| x == k.value()));
+                (kept.into_iter().collect(), filtered.into_iter().collect())
+            }
+        }
+    }
+
+    fn update(
+        &self,
+        descriptor: &Arc<Descriptor>,
+        labels: &LabelSet,
+        value: f64,
+        span: Option<(&TraceId, &SpanId)>,
+    ) {
+        let now = self.clock.now();
+        let (labels, filtered_labels) = self.split_labels(labels);
+        let mut current = self.current.lock().unwrap();
+        let (aggregation, reservoir) = current
+            .1
+            .entry((descriptor.clone(), labels))
+            .or_insert_with(|| {
+                (
+                    self.selector.aggregation_for(descriptor),
+                    self.selector.reservoir_for(descriptor),
+                )
+            });
+        aggregation.update(value, now);
+        if let Some((trace_id, span_id)) = span {
+            reservoir.offer(Exemplar {
+                value,
+                timestamp: now,
+                trace_id: trace_id.clone(),
+                span_id: span_id.clone(),
+                filtered_labels,
+            });
+        }
+    }
+
+    /// Runs the observers and returns what was recorded since the previous call.
+    ///
+    /// Observers run without any lock held, so they may create instruments and observers.
+    pub fn collect(&self) -> Vec<Record> {
+        let observers = self.observers.lock().unwrap().clone();
+        let observations: Vec<_> = observers
+            .iter()
+            .map(|(descriptor, callback)| {
+                let mut result = ObserverResult::default();
+                callback(&mut result);
+                (descriptor.clone(), result.into_observations())
+            })
+            .collect();
+        for (descriptor, xs) in observations {
+            for (labels, value) in xs {
+                self.record(&descriptor, &labels, value);
+            }
+        }
+
+        let end_time = self.clock.now();
+        let (start_time, state) = {
+            let mut current = self.current.lock().unwrap();
+            std::mem::replace(&mut *current, (end_time, HashMap::new()))
+        };
+        state
+            .into_iter()
+            .map(|((descriptor, labels), (aggregation, reservoir))| Record {
+                descriptor,
+                labels,
+                aggregation,
+                start_time,
+                end_time,
+                exemplars: reservoir.into_exemplars(),
+            })
+            .collect()
+    }
+}
+
+impl Default for Accumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MeterCore for Accumulator {
+    fn record(&self, descriptor: &Arc<Descriptor>, labels: &LabelSet, value: f64) {
+        self.update(descriptor, labels, value, None);
+    }
+
+    fn record_in_span(
+        &self,
+        descriptor: &Arc<Descriptor>,
+        labels: &LabelSet,
+        value: f64,
+        trace_id: &TraceId,
+        span_id: &SpanId,
+    ) {
+        self.update(descriptor, labels, value, Some((trace_id, span_id)));
+    }
+
+    fn register_observer(&self, descriptor: Arc<Descriptor>, callback: ObserverCallback) {
+        self.observers
+            .lock()
+            .unwrap()
+            .push((descriptor, Arc::from(callback)));
+    }
+}
+
+/// Collects an `Accumulator` and exports the records in the exporter's temporality.
+pub struct PeriodicCollector<E: MetricExporter> {
+    accumulator: Arc<Accumulator>,
+    exporter: E,
+    interval: Duration,
+    cumulative: HashMap<Key, Record>,
+}
+
+impl<E: MetricExporter> PeriodicCollector<E> {
+    pub fn new(accumulator: Arc<Accumulator>, exporter: E) -> Self {
+        Self {
+            accumulator,
+            exporter,
+            interval: Duration::from_secs(60),
+            cumulative: HashMap::new(),
+        }
+    }
+
+    pub fn with_interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    pub fn meter(&self) -> Meter {
+        Meter::new(self.accumulator.clone())
+    }
+
+    pub fn exporter(&self) -> &E {
+        &self.exporter
+    }
+
+    /// Collects once and exports the result.
+    pub fn collect(&mut self) -> ResultCode {
+        let deltas = self.accumulator.collect();
+        match self.exporter.temporality() {
+            Temporality::Delta => self.exporter.export(&deltas),
+            Temporality::Cumulative => {
+                for record in deltas {
+                    let key = (record.descriptor.clone(), record.labels.clone());
+                    match self.cumulative.get_mut(&key) {
+                        Some(total) => {
+                            total.aggregation.merge(&record.aggregation);
+                            total.end_time = record.end_time;
+                            if !record.exemplars.is_empty() {
+                                total.exemplars = record.exemplars;
+                            }
+                        }
+                        None => {
+                            self.cumulative.insert(key, record);
+                        }
+                    }
+                }
+                let batch: Vec<Record> = self.cumulative.values().cloned().collect();
+                self.exporter.export(&batch)
+            }
+        }
+    }
+
+    pub fn shutdown(&mut self) {
+        self.collect();
+        self.exporter.shutdown();
+    }
+}
+
+impl<E: MetricExporter + Send + 'static> PeriodicCollector<E> {
+    /// Collects every interval on a background thread until the handle is shut down.
+    pub fn start(mut self) -> CollectorHandle<E> {
+        let (stop, stopped) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(self.interval) {
+                self.collect();
+            }
+            self.shutdown();
+            self
+        });
+        CollectorHandle { stop, thread }
+    }
+}
+
+pub struct CollectorHandle<E: MetricExporter> {
+    stop: Sender<()>,
+    thread: JoinHandle<PeriodicCollector<E>>,
+}
+
+impl<E: MetricExporter> CollectorHandle<E> {
+    /// Stops the thread after a final collection and returns the collector.
+    pub fn shutdown(self) -> thread::Result<PeriodicCollector<E>> {
+        let _ = self.stop.send(());
+        self.thread.join()
+    }
+}
+
+#[cfg(test)]
+#[derive(Clone)]
+pub(crate) struct TestExporter {
+    pub(crate) temporality: Temporality,
+    pub(crate) batches: Arc<Mutex<Vec<Vec<Record>>>>,
+}
+
+#[cfg(test)]
+impl MetricExporter for TestExporter {
+    fn temporality(&self) -> Temporality {
+        self.temporality
+    }
+
+    fn export(&mut self, batch: &[Record]) -> ResultCode {
+        self.batches.lock().unwrap().push(batch.to_vec());
+        ResultCode::Success
+    }
+
+    fn shutdown(&mut self) {}
+}
+
+#[test]
+fn collector_delta_and_cumulative() {
+    use crate::api::registry::Variable;
+    use crate::api::trace::clock::ManualClock;
+    use crate::api::unit::Unit;
+
+    let sums = |temporality| {
+        let clock = Arc::new(ManualClock::new(Timestamp::from_unix_nanos(0).unwrap()));
+        let accumulator = Arc::new(Accumulator::new().with_clock(clock.clone()));
+        let exporter = TestExporter {
+            temporality,
+            batches: Arc::new(Mutex::new(Vec::new())),
+        };
+        let mut collector = PeriodicCollector::new(accumulator, exporter.clone());
+        let meter = collector.meter();
+        let requests = meter.new_counter(Variable::new("requests", Unit::Dimensionless));
+        let heap = meter.new_observer(Variable::new("heap", Unit::Bytes), |x| {
+            x.observe(64.0, &LabelSet::default())
+        });
+        let get = LabelSet::try_from_pairs(&[("method", "GET")]).unwrap();
+
+        requests.add(1.0, &get);
+        requests.add(2.0, &get);
+        clock.advance(Duration::from_secs(10));
+        collector.collect();
+        requests.add(4.0, &get);
+        clock.advance(Duration::from_secs(10));
+        collector.collect();
+
+        let batches = exporter.batches.lock().unwrap();
+        batches
+            .iter()
+            .map(|batch| {
+                let sum = batch
+                    .iter()
+                    .find(|x| x.descriptor().name() == "requests")
+                    .unwrap();
+                let observed = batch
+                    .iter()
+                    .find(|x| x.descriptor() == heap.descriptor())
+                    .unwrap();
+                assert!(matches!(
+                    observed.aggregation(),
+                    Aggregation::LastValue { value, .. } if *value == 64.0
+                ));
+                (
+                    sum.aggregation().clone(),
+                    sum.start_time().as_millis().unwrap(),
+                    sum.end_time().as_millis().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        sums(Temporality::Delta),
+        vec![
+            (Aggregation::Sum(3.0), 0, 10_000),
+            (Aggregation::Sum(4.0), 10_000, 20_000),
+        ]
+    );
+    assert_eq!(
+        sums(Temporality::Cumulative),
+        vec![
+            (Aggregation::Sum(3.0), 0, 10_000),
+            (Aggregation::Sum(7.0), 0, 20_000),
+        ]
+    );
+}
+
+#[test]
+fn observers_may_register_observers() {
+    use crate::api::registry::Variable;
+    use crate::api::unit::Unit;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let accumulator = Arc::new(Accumulator::new());
+    let meter = Meter::new(accumulator.clone());
+    let registered = AtomicBool::new(false);
+    let _outer = meter.new_observer(Variable::new("outer", Unit::Dimensionless), {
+        let meter = Meter::new(accumulator.clone());
+        move |result| {
+            result.observe(1.0, &LabelSet::default());
+            if !registered.swap(true, Ordering::SeqCst) {
+                meter.new_observer(Variable::new("inner", Unit::Dimensionless), |result| {
+                    result.observe(2.0, &LabelSet::default())
+                });
+            }
+        }
+    });
+    assert_eq!(accumulator.collect().len(), 1);
+    let mut names: Vec<String> = accumulator
+        .collect()
+        .iter()
+        .map(|x| x.descriptor().name().to_owned())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["inner", "outer"]);
+}
+
+#[test]
+fn accumulator_keeps_exemplars() {
+    use crate::api::registry::Variable;
+    use crate::api::trace::span_context::{SpanContext, TraceOption, TraceState};
+    use crate::api::unit::Unit;
+    use std::num::{NonZeroU128, NonZeroU64};
+
+    let accumulator = Arc::new(
+        Accumulator::new()
+            .with_selector(Box::new(
+                SimpleSelector::new()
+                    .with_histogram_boundaries(&[10.0, 100.0])
+                    .with_exemplars(1),
+            ))
+            .with_label_keys(&["route"]),
+    );
+    let meter = Meter::new(accumulator.clone());
+    let latency = meter.new_histogram(Variable::new("latency", Unit::Milliseconds));
+    let trace_id = TraceId::new(NonZeroU128::new(7).unwrap());
+    let span = |id, trace_option| {
+        SpanContext::new(
+            &trace_id,
+            SpanId::new(NonZeroU64::new(id).unwrap()),
+            trace_option,
+            TraceState::empty(),
+        )
+    };
+    let labels = LabelSet::try_from_pairs(&[("route", "/a"), ("user", "42")]).unwrap();
+
+    latency.record_in_span(5.0, &labels, &span(1, TraceOption::MASK_SAMPLE));
+    latency.record_in_span(50.0, &labels, &span(2, TraceOption::MASK_SAMPLE));
+    latency.record_in_span(60.0, &labels, &span(3, TraceOption::MASK_UNUSED));
+    latency.record(500.0, &labels);
+
+    let records = accumulator.collect();
+    assert_eq!(records.len(), 1);
+    assert_eq!(
+        records[0].labels(),
+        &LabelSet::try_from_pairs(&[("route", "/a")]).unwrap()
+    );
+    let exemplars: Vec<(f64, u64)> = records[0]
+        .exemplars()
+        .iter()
+        .map(|x| (x.value(), x.span_id().as_u64()))
+        .collect();
+    assert_eq!(exemplars, vec![(5.0, 1), (50.0, 2)]);
+    assert_eq!(
+        records[0].exemplars()[0].filtered_labels(),
+        &LabelSet::try_from_pairs(&[("user", "42")]).unwrap()
+    );
+}
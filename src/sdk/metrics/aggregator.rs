@@ -0,0 +1,314 @@
+//! [Aggregations](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/sdk-metrics.md#aggregations)
+
+use std::sync::Arc;
+
+use crate::api::metrics::{Descriptor, InstrumentKind};
+use crate::api::trace::Timestamp;
+use crate::sdk::metrics::exemplar::ExemplarReservoir;
+
+/// Aggregated state of one metric and label set.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Aggregation {
+    Sum(f64),
+    /// `timestamp` is `None` until the first value arrives.
+    LastValue {
+        value: f64,
+        timestamp: Option<Timestamp>,
+    },
+    MinMaxSumCount {
+        min: f64,
+        max: f64,
+        sum: f64,
+        count: u64,
+    },
+    /// `counts[i]` holds values `<= boundaries[i]`; the last count is the overflow bucket.
+    Histogram {
+        boundaries: Arc<[f64]>,
+        counts: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+impl Aggregation {
+    pub fn sum() -> Self {
+        Aggregation::Sum(0.0)
+    }
+
+    pub fn last_value() -> Self {
+        Aggregation::LastValue {
+            value: 0.0,
+            timestamp: None,
+        }
+    }
+
+    pub fn min_max_sum_count() -> Self {
+        Aggregation::MinMaxSumCount {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    /// `boundaries` are sorted and deduplicated.
+    pub fn histogram(boundaries: &[f64]) -> Self {
+        let mut b: Vec<f64> = boundaries.iter().cloned().filter(|x| !x.is_nan()).collect();
+        b.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        b.dedup();
+        Aggregation::Histogram {
+            counts: vec![0; b.len() + 1],
+            boundaries: b.into(),
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    pub fn update(&mut self, value: f64, timestamp: Timestamp) {
+        match self {
+            Aggregation::Sum(sum) => *sum += value,
+            Aggregation::LastValue {
+                value: v,
+                timestamp: t,
+            } => {
+                *v = value;
+                *t = Some(timestamp);
+            }
+            Aggregation::MinMaxSumCount {
+                min,
+                max,
+                sum,
+                count,
+            } => {
+                *min = min.min(value);
+                *max = max.max(value);
+                *sum += value;
+                *count += 1;
+            }
+            Aggregation::Histogram {
+                boundaries,
+                counts,
+                sum,
+                count,
+            } => {
+                let i = boundaries
+                    .iter()
+                    .position(|x| value <= *x)
+                    .unwrap_or(boundaries.len());
+                counts[i] += 1;
+                *sum += value;
+                *count += 1;
+            }
+        }
+    }
+
+    /// Folds a later delta into `self`, e.g. to build cumulative state.
+    pub fn merge(&mut self, other: &Self) {
+        match (self, other) {
+            (Aggregation::Sum(a), Aggregation::Sum(b)) => *a += b,
+            (
+                Aggregation::LastValue {
+                    value: v,
+                    timestamp: t,
+                },
+                Aggregation::LastValue {
+                    value,
+                    timestamp: Some(timestamp),
+                },
+            ) if t.map_or(true, |x| x <= *timestamp) => {
+                *v = *value;
+                *t = Some(*timestamp);
+            }
+            (
+                Aggregation::MinMaxSumCount {
+                    min,
+                    max,
+                    sum,
+                    count,
+                },
+                Aggregation::MinMaxSumCount {
+                    min: min_b,
+                    max: max_b,
+                    sum: sum_b,
+                    count: count_b,
+                },
+            ) => {
+                *min = min.min(*min_b);
+                *max = max.max(*max_b);
+                *sum += sum_b;
+                *count += count_b;
+            }
+            (
+                Aggregation::Histogram {
+                    boundaries,
+                    counts,
+                    sum,
+                    count,
+                },
+                Aggregation::Histogram {
+                    boundaries: boundaries_b,
+                    counts: counts_b,
+                    sum: sum_b,
+                    count: count_b,
+                },
+            ) if boundaries == boundaries_b => {
+                for (a, b) in counts.iter_mut().zip(counts_b.iter()) {
+                    *a += b;
+                }
+                *sum += sum_b;
+                *count += count_b;
+            }
+            _ => {}
+        }
+    }
+
+    /// Same kind of aggregation with nothing recorded.
+    pub fn empty(&self) -> Self {
+        match self {
+            Aggregation::Sum(_) => Self::sum(),
+            Aggregation::LastValue { .. } => Self::last_value(),
+            Aggregation::MinMaxSumCount { .. } => Self::min_max_sum_count(),
+            Aggregation::Histogram { boundaries, .. } => Aggregation::Histogram {
+                boundaries: boundaries.clone(),
+                counts: vec![0; boundaries.len() + 1],
+                sum: 0.0,
+                count: 0,
+            },
+        }
+    }
+}
+
+/// Chooses the aggregation of each instrument.
+pub trait AggregatorSelector: Send + Sync {
+    fn aggregation_for(&self, descriptor: &Descriptor) -> Aggregation;
+
+    /// Exemplars kept next to each aggregation; none by default.
+    fn reservoir_for(&self, _descriptor: &Descriptor) -> ExemplarReservoir {
+        ExemplarReservoir::none()
+    }
+}
+
+/// Sums for counters, last values for gauges and observers, and either
+/// explicit-bucket histograms or min/max/sum/count for histograms.
+#[derive(Debug, Clone)]
+pub struct SimpleSelector {
+    histogram: Aggregation,
+    exemplars: usize,
+}
+
+impl SimpleSelector {
+    pub fn new() -> Self {
+        Self {
+            histogram: Aggregation::histogram(&[
+                5.0, 10.0, 25.0, 50.0, 75.0, 100.0, 250.0, 500.0, 750.0, 1000.0, 2500.0, 5000.0,
+                7500.0, 10000.0,
+            ]),
+            exemplars: 0,
+        }
+    }
+
+    pub fn with_histogram_boundaries(self, boundaries: &[f64]) -> Self {
+        Self {
+            histogram: Aggregation::histogram(boundaries),
+            ..self
+        }
+    }
+
+    pub fn with_min_max_sum_count(self) -> Self {
+        Self {
+            histogram: Aggregation::min_max_sum_count(),
+            ..self
+        }
+    }
+
+    /// Keeps exemplars for sums and histograms: one per bucket of explicit-bucket
+    /// histograms, otherwise up to `size`. `0` disables them.
+    pub fn with_exemplars(self, size: usize) -> Self {
+        Self {
+            exemplars: size,
+            ..self
+        }
+    }
+}
+
+impl Default for SimpleSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AggregatorSelector for SimpleSelector {
+    fn aggregation_for(&self, descriptor: &Descriptor) -> Aggregation {
+        match descriptor.kind() {
+            InstrumentKind::Counter | InstrumentKind::UpDownCounter => Aggregation::sum(),
+            InstrumentKind::Gauge | InstrumentKind::Observer => Aggregation::last_value(),
+            InstrumentKind::Histogram => self.histogram.empty(),
+        }
+    }
+
+    fn reservoir_for(&self, descriptor: &Descriptor) -> ExemplarReservoir {
+        match (self.exemplars, descriptor.kind(), &self.histogram) {
+            (0, _, _) | (_, InstrumentKind::Gauge, _) | (_, InstrumentKind::Observer, _) => {
+                ExemplarReservoir::none()
+            }
+            (_, InstrumentKind::Histogram, Aggregation::Histogram { boundaries, .. }) => {
+                ExemplarReservoir::histogram_buckets(boundaries)
+            }
+            (size, _, _) => ExemplarReservoir::fixed_size(size),
+        }
+    }
+}
+
+#[test]
+fn aggregations_update_and_merge() {
+    let t = |x| Timestamp::from_unix_nanos(x).unwrap();
+
+    let mut h = Aggregation::histogram(&[10.0, 1.0, 5.0]);
+    for x in &[0.5, 1.0, 3.0, 7.0, 100.0] {
+        h.update(*x, t(1));
+    }
+    let mut total = h.empty();
+    total.merge(&h);
+    total.merge(&h);
+    match total {
+        Aggregation::Histogram {
+            boundaries,
+            counts,
+            sum,
+            count,
+        } => {
+            assert_eq!(&*boundaries, &[1.0, 5.0, 10.0]);
+            assert_eq!(counts, vec![4, 2, 2, 2]);
+            assert_eq!(sum, 223.0);
+            assert_eq!(count, 10);
+        }
+        _ => unreachable!(),
+    }
+
+    let mut m = Aggregation::min_max_sum_count();
+    m.update(3.0, t(1));
+    m.update(-1.0, t(2));
+    assert_eq!(
+        m,
+        Aggregation::MinMaxSumCount {
+            min: -1.0,
+            max: 3.0,
+            sum: 2.0,
+            count: 2
+        }
+    );
+
+    let mut last = Aggregation::last_value();
+    let mut newer = Aggregation::last_value();
+    newer.update(2.0, t(5));
+    let mut older = Aggregation::last_value();
+    older.update(1.0, t(3));
+    last.merge(&newer);
+    last.merge(&older);
+    assert_eq!(last, newer);
+
+    let mut s = Aggregation::sum();
+    s.update(2.5, t(1));
+    s.merge(&s.clone());
+    assert_eq!(s, Aggregation::Sum(5.0));
+}
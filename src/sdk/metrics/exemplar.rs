@@ -0,0 +1,155 @@
+//! [Exemplars](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/sdk-metrics.md#exemplars):
+//! sample measurements kept with the ids of the span they were made in.
+
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::api::metrics::labels::LabelSet;
+use crate::api::trace::span_context::{SpanId, TraceId};
+use crate::api::trace::Timestamp;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exemplar {
+    pub(crate) value: f64,
+    pub(crate) timestamp: Timestamp,
+    pub(crate) trace_id: TraceId,
+    pub(crate) span_id: SpanId,
+    pub(crate) filtered_labels: LabelSet,
+}
+
+impl Exemplar {
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn timestamp(&self) -> &Timestamp {
+        &self.timestamp
+    }
+
+    pub fn trace_id(&self) -> &TraceId {
+        &self.trace_id
+    }
+
+    pub fn span_id(&self) -> &SpanId {
+        &self.span_id
+    }
+
+    /// Labels of the measurement which are not part of its time series.
+    pub fn filtered_labels(&self) -> &LabelSet {
+        &self.filtered_labels
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Sampling {
+    /// Uniform sample of everything offered in the interval.
+    FixedSize,
+    /// Last offered value of each histogram bucket.
+    Buckets(Arc<[f64]>),
+}
+
+/// Keeps some of the exemplars offered to one aggregation during a collection interval.
+#[derive(Debug, Clone)]
+pub struct ExemplarReservoir {
+    sampling: Sampling,
+    exemplars: Vec<Option<Exemplar>>,
+    offered: u64,
+}
+
+impl ExemplarReservoir {
+    /// Keeps nothing.
+    pub fn none() -> Self {
+        Self::fixed_size(0)
+    }
+
+    /// Keeps up to `size` exemplars, each offered one equally likely.
+    pub fn fixed_size(size: usize) -> Self {
+        Self {
+            sampling: Sampling::FixedSize,
+            exemplars: vec![None; size],
+            offered: 0,
+        }
+    }
+
+    /// Keeps the last exemplar of each bucket of a histogram with these `boundaries`.
+    pub fn histogram_buckets(boundaries: &[f64]) -> Self {
+        let mut b: Vec<f64> = boundaries.iter().cloned().filter(|x| !x.is_nan()).collect();
+        b.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        b.dedup();
+        Self {
+            exemplars: vec![None; b.len() + 1],
+            sampling: Sampling::Buckets(b.into()),
+            offered: 0,
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.exemplars.is_empty()
+    }
+
+    pub fn offer(&mut self, exemplar: Exemplar) {
+        if self.is_none() {
+            return;
+        }
+        self.offered += 1;
+        let i = match &self.sampling {
+            Sampling::FixedSize if self.offered as usize <= self.exemplars.len() => {
+                self.offered as usize - 1
+            }
+            Sampling::FixedSize => {
+                let j = rand::thread_rng().gen_range(0, self.offered) as usize;
+                if j >= self.exemplars.len() {
+                    return;
+                }
+                j
+            }
+            Sampling::Buckets(boundaries) => boundaries
+                .iter()
+                .position(|x| exemplar.value <= *x)
+                .unwrap_or(boundaries.len()),
+        };
+        self.exemplars[i] = Some(exemplar);
+    }
+
+    /// Kept exemplars, oldest first.
+    pub fn into_exemplars(self) -> Vec<Exemplar> {
+        let mut exemplars: Vec<Exemplar> = self.exemplars.into_iter().flatten().collect();
+        exemplars.sort_by_key(|x| x.timestamp);
+        exemplars
+    }
+}
+
+#[test]
+fn reservoirs_keep_offered_exemplars() {
+    use std::num::{NonZeroU128, NonZeroU64};
+
+    let exemplar = |value, nanos| Exemplar {
+        value,
+        timestamp: Timestamp::from_unix_nanos(nanos).unwrap(),
+        trace_id: TraceId::new(NonZeroU128::new(1).unwrap()),
+        span_id: SpanId::new(NonZeroU64::new(nanos).unwrap()),
+        filtered_labels: LabelSet::default(),
+    };
+
+    let mut buckets = ExemplarReservoir::histogram_buckets(&[10.0, 1.0]);
+    for (i, x) in [0.5, 20.0, 0.7, 5.0].iter().enumerate() {
+        buckets.offer(exemplar(*x, i as u64 + 1));
+    }
+    let values: Vec<f64> = buckets
+        .into_exemplars()
+        .iter()
+        .map(Exemplar::value)
+        .collect();
+    assert_eq!(values, vec![20.0, 0.7, 5.0]);
+
+    let mut fixed = ExemplarReservoir::fixed_size(2);
+    for i in 1..=100 {
+        fixed.offer(exemplar(i as f64, i));
+    }
+    assert_eq!(fixed.into_exemplars().len(), 2);
+
+    let mut none = ExemplarReservoir::none();
+    none.offer(exemplar(1.0, 1));
+    assert!(none.into_exemplars().is_empty());
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use crate::api::metrics::labels::LabelSet;
+use crate::api::metrics::Descriptor;
+use crate::api::trace::Timestamp;
+use crate::sdk::metrics::aggregator::Aggregation;
+use crate::sdk::metrics::exemplar::Exemplar;
+use crate::sdk::trace::export::ResultCode;
+
+/// Whether exported sums cover the last interval or everything since the start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Temporality {
+    Delta,
+    Cumulative,
+}
+
+/// Aggregated value of one metric and label set over `[start_time, end_time]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub(crate) descriptor: Arc<Descriptor>,
+    pub(crate) labels: LabelSet,
+    pub(crate) aggregation: Aggregation,
+    pub(crate) start_time: Timestamp,
+    pub(crate) end_time: Timestamp,
+    pub(crate) exemplars: Vec<Exemplar>,
+}
+
+impl Record {
+    pub fn descriptor(&self) -> &Descriptor {
+        &self.descriptor
+    }
+
+    pub fn labels(&self) -> &LabelSet {
+        &self.labels
+    }
+
+    pub fn aggregation(&self) -> &Aggregation {
+        &self.aggregation
+    }
+
+    pub fn start_time(&self) -> &Timestamp {
+        &self.start_time
+    }
+
+    pub fn end_time(&self) -> &Timestamp {
+        &self.end_time
+    }
+
+    /// Sampled measurements of the latest interval with any, oldest first.
+    pub fn exemplars(&self) -> &[Exemplar] {
+        &self.exemplars
+    }
+}
+
+/// Receives collected metrics and sends them to a backend.
+///
+/// [Exporter spec](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/sdk-metrics.md)
+pub trait MetricExporter {
+    fn temporality(&self) -> Temporality {
+        Temporality::Cumulative
+    }
+
+    fn export(&mut self, batch: &[Record]) -> ResultCode;
+
+    fn shutdown(&mut self);
+}
@@ -0,0 +1,284 @@
+//! [Resource detectors](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/resource/sdk.md#detecting-resource-information-from-the-environment)
+//! describing the process, host and deployment a program runs in.
+
+use std::env;
+use std::fs;
+
+use crate::api::resources::{Resource, ResourceValue};
+use crate::api::semconv::resource::{container, host, k8s, os, process, service};
+
+const OTEL_RESOURCE_ATTRIBUTES: &str = "OTEL_RESOURCE_ATTRIBUTES";
+const OTEL_SERVICE_NAME: &str = "OTEL_SERVICE_NAME";
+
+pub trait ResourceDetector {
+    /// Labels found; empty if there is nothing to detect.
+    fn detect(&self) -> Resource;
+}
+
+/// Merges the detected resources in order, so later detectors win on duplicate labels.
+pub fn detect(detectors: &[&dyn ResourceDetector]) -> Resource {
+    detectors
+        .iter()
+        .fold(Resource::default(), |acc, x| acc.merge(&x.detect()))
+}
+
+/// Process, host and Kubernetes labels, overridden by those configured in the environment.
+pub fn default_resource() -> Resource {
+    detect(&[
+        &ProcessResourceDetector,
+        &HostResourceDetector,
+        &KubernetesResourceDetector,
+        &EnvResourceDetector,
+    ])
+}
+
+/// Sets a label; `name` is skipped if it is not a valid label name.
+fn upsert<V: Into<ResourceValue>>(resource: &mut Resource, name: &str, value: V) {
+    let _ = resource.try_upsert(name, value);
+}
+
+/// `OTEL_RESOURCE_ATTRIBUTES` as percent-encoded `key=value,...` pairs and `OTEL_SERVICE_NAME`,
+/// which takes precedence for `service.name`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EnvResourceDetector;
+
+impl ResourceDetector for EnvResourceDetector {
+    fn detect(&self) -> Resource {
+        from_env_values(
+            env::var(OTEL_RESOURCE_ATTRIBUTES).ok().as_deref(),
+            env::var(OTEL_SERVICE_NAME).ok().as_deref(),
+        )
+    }
+}
+
+fn from_env_values(attributes: Option<&str>, service_name: Option<&str>) -> Resource {
+    let mut r = Resource::default();
+    for pair in attributes.unwrap_or_default().split(',') {
+        if let Some((k, v)) = pair.split_once('=') {
+            if let Some(v) = percent_decode(v.trim()) {
+                upsert(&mut r, k.trim(), v);
+            }
+        }
+    }
+    if let Some(name) = service_name.map(str::trim).filter(|x| !x.is_empty()) {
+        upsert(&mut r, service::NAME.name(), name);
+    }
+    r
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Pid, executable, command line and Rust version of the current process.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProcessResourceDetector;
+
+impl ResourceDetector for ProcessResourceDetector {
+    fn detect(&self) -> Resource {
+        let mut r = Resource::default();
+        upsert(&mut r, process::PID.name(), std::process::id());
+        if let Ok(exe) = env::current_exe() {
+            if let Some(name) = exe.file_name().and_then(|x| x.to_str()) {
+                upsert(&mut r, process::EXECUTABLE_NAME.name(), name);
+            }
+            if let Some(path) = exe.to_str() {
+                upsert(&mut r, process::EXECUTABLE_PATH.name(), path);
+            }
+        }
+        let args: Vec<String> = env::args_os()
+            .map(|x| x.to_string_lossy().into_owned())
+            .collect();
+        if let Some(command) = args.first() {
+            upsert(&mut r, process::COMMAND.name(), command.as_str());
+        }
+        upsert(&mut r, process::COMMAND_LINE.name(), args.join(" "));
+        upsert(&mut r, process::RUNTIME_NAME.name(), "rustc");
+        let version = env!("OT_RUSTC_VERSION");
+        if !version.is_empty() {
+            upsert(&mut r, process::RUNTIME_VERSION.name(), version);
+        }
+        r
+    }
+}
+
+/// Host name, OS and CPU architecture.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HostResourceDetector;
+
+impl ResourceDetector for HostResourceDetector {
+    fn detect(&self) -> Resource {
+        let mut r = Resource::default();
+        let name = env::var("HOSTNAME")
+            .ok()
+            .or_else(|| fs::read_to_string("/proc/sys/kernel/hostname").ok())
+            .or_else(|| fs::read_to_string("/etc/hostname").ok());
+        if let Some(name) = name.as_deref().map(str::trim).filter(|x| !x.is_empty()) {
+            upsert(&mut r, host::NAME.name(), name);
+        }
+        upsert(&mut r, os::TYPE.name(), os_type(env::consts::OS));
+        upsert(&mut r, host::ARCH.name(), host_arch(env::consts::ARCH));
+        r
+    }
+}
+
+/// The semantic-convention `os.type` for a Rust target OS, or the OS itself if there is none.
+fn os_type(os: &str) -> &str {
+    match os {
+        "macos" => "darwin",
+        "dragonfly" => "dragonflybsd",
+        _ => os,
+    }
+}
+
+/// The semantic-convention `host.arch` for a Rust target architecture, or the architecture
+/// itself if there is none.
+fn host_arch(arch: &str) -> &str {
+    match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "arm" => "arm32",
+        "powerpc" => "ppc32",
+        "powerpc64" => "ppc64",
+        _ => arch,
+    }
+}
+
+/// Pod labels from the downward-API environment variables `K8S_POD_NAME`, `K8S_POD_UID`,
+/// `K8S_NAMESPACE_NAME`, `K8S_NODE_NAME` and `K8S_CONTAINER_NAME` (or the same without
+/// `K8S_`), and the container id from `/proc/self/cgroup`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct KubernetesResourceDetector;
+
+impl ResourceDetector for KubernetesResourceDetector {
+    fn detect(&self) -> Resource {
+        from_kubernetes(
+            |x| env::var(x).ok(),
+            fs::read_to_string("/proc/self/cgroup").ok().as_deref(),
+        )
+    }
+}
+
+fn from_kubernetes(env: impl Fn(&str) -> Option<String>, cgroup: Option<&str>) -> Resource {
+    let mut r = Resource::default();
+    let vars = [
+        ("POD_NAME", k8s::POD_NAME),
+        ("POD_UID", k8s::POD_UID),
+        ("NAMESPACE_NAME", k8s::NAMESPACE_NAME),
+        ("NODE_NAME", k8s::NODE_NAME),
+        ("CONTAINER_NAME", k8s::CONTAINER_NAME),
+    ];
+    for (var, key) in vars.iter() {
+        let value = env(&format!("K8S_{}", var)).or_else(|| env(var));
+        if let Some(value) = value.as_deref().map(str::trim).filter(|x| !x.is_empty()) {
+            upsert(&mut r, key.name(), value);
+        }
+    }
+    if let Some(id) = cgroup.and_then(container_id) {
+        upsert(&mut r, container::ID.name(), id);
+    }
+    r
+}
+
+/// The 64 hex digit id ending a line of `/proc/self/cgroup`, e.g. `.../docker-<id>.scope`.
+fn container_id(cgroup: &str) -> Option<&str> {
+    cgroup.lines().find_map(|line| {
+        let last = line.rsplit('/').next()?;
+        let last = last.strip_suffix(".scope").unwrap_or(last);
+        let id = last.get(last.len().saturating_sub(64)..)?;
+        if id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit()) {
+            Some(id)
+        } else {
+            None
+        }
+    })
+}
+
+#[test]
+fn env_resource_attributes() {
+    let r = from_env_values(
+        Some("service.name=shop, deployment.environment=prod,bad,k8s.pod.name=a%2Cb"),
+        None,
+    );
+    assert_eq!(r.get("service.name").and_then(|x| x.as_str()), Some("shop"));
+    assert_eq!(
+        r.get("deployment.environment").and_then(|x| x.as_str()),
+        Some("prod")
+    );
+    assert_eq!(r.get("k8s.pod.name").and_then(|x| x.as_str()), Some("a,b"));
+    assert!(r.get("bad").is_none());
+
+    let r = from_env_values(Some("service.name=shop"), Some("cart"));
+    assert_eq!(r.get("service.name").and_then(|x| x.as_str()), Some("cart"));
+}
+
+#[test]
+fn kubernetes_and_container_id() {
+    let id = "3f4b2a1c9d8e7f6a5b4c3d2e1f0a9b8c7d6e5f4a3b2c1d0e9f8a7b6c5d4e3f2a";
+    let cgroup = format!(
+        "12:pids:/kubepods/burstable/pod1234/{}\n0::/system.slice/docker-{}.scope\n",
+        id, id
+    );
+    let env = |x: &str| match x {
+        "K8S_POD_NAME" => Some("web-0".to_owned()),
+        "NAMESPACE_NAME" => Some("shop".to_owned()),
+        _ => None,
+    };
+    let r = from_kubernetes(env, Some(&cgroup));
+    assert_eq!(
+        r.get("k8s.pod.name").and_then(|x| x.as_str()),
+        Some("web-0")
+    );
+    assert_eq!(
+        r.get("k8s.namespace.name").and_then(|x| x.as_str()),
+        Some("shop")
+    );
+    assert_eq!(r.get("container.id").and_then(|x| x.as_str()), Some(id));
+    assert_eq!(container_id("0::/user.slice\n"), None);
+    assert_eq!(
+        container_id(&format!("0::/system.slice/docker-{}.scope", id)),
+        Some(id)
+    );
+}
+
+#[test]
+fn host_values_follow_semconv() {
+    assert_eq!(os_type("macos"), "darwin");
+    assert_eq!(os_type("linux"), "linux");
+    assert_eq!(host_arch("x86_64"), "amd64");
+    assert_eq!(host_arch("aarch64"), "arm64");
+    assert_eq!(host_arch("s390x"), "s390x");
+}
+
+#[test]
+fn detectors_merge_in_order() {
+    struct Fixed(&'static str);
+
+    impl ResourceDetector for Fixed {
+        fn detect(&self) -> Resource {
+            let mut r = Resource::default();
+            r.try_upsert("service.name", self.0).unwrap();
+            r
+        }
+    }
+
+    let r = detect(&[&ProcessResourceDetector, &Fixed("a"), &Fixed("b")]);
+    assert_eq!(r.get("service.name").and_then(|x| x.as_str()), Some("b"));
+    assert_eq!(
+        r.get("process.pid"),
+        Some(&ResourceValue::Int(i64::from(std::process::id())))
+    );
+    assert!(r.get("process.runtime.version").is_some());
+}
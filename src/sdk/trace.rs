@@ -0,0 +1,5 @@
+pub mod export;
+pub mod processor;
+pub mod provider;
+pub mod sampler;
+pub mod span_metrics;
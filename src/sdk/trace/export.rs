@@ -0,0 +1,30 @@
+use crate::api::trace::span_data::SpanData;
+
+/// Result of an export attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultCode {
+    Success,
+    /// The batch must be dropped, e.g. it could not be serialized.
+    FailedNotRetryable,
+    /// The batch may be exported again later, e.g. the backend was unreachable.
+    FailedRetryable,
+}
+
+/// Receives finished spans and sends them to a backend.
+///
+/// [SpanExporter spec](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/sdk-tracing.md#span-exporter)
+pub trait SpanExporter {
+    fn export(&mut self, batch: &[SpanData]) -> ResultCode;
+
+    fn shutdown(&mut self);
+}
+
+impl<E: SpanExporter + ?Sized> SpanExporter for Box<E> {
+    fn export(&mut self, batch: &[SpanData]) -> ResultCode {
+        (**self).export(batch)
+    }
+
+    fn shutdown(&mut self) {
+        (**self).shutdown()
+    }
+}
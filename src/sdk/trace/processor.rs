@@ -0,0 +1,188 @@
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::api::trace::span_data::SpanData;
+use crate::sdk::trace::export::SpanExporter;
+
+/// Hooks called as spans end, e.g. to export them or derive metrics from them.
+///
+/// [SpanProcessor spec](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/sdk-tracing.md#span-processor)
+pub trait SpanProcessor: Send + Sync {
+    fn on_end(&self, span: &SpanData);
+
+    fn shutdown(&self) {}
+}
+
+/// Exports every span as soon as it ends.
+pub struct SimpleSpanProcessor<E: SpanExporter> {
+    exporter: Mutex<E>,
+}
+
+impl<E: SpanExporter> SimpleSpanProcessor<E> {
+    pub fn new(exporter: E) -> Self {
+        Self {
+            exporter: Mutex::new(exporter),
+        }
+    }
+}
+
+impl<E: SpanExporter + Send> SpanProcessor for SimpleSpanProcessor<E> {
+    fn on_end(&self, span: &SpanData) {
+        self.exporter
+            .lock()
+            .unwrap()
+            .export(std::slice::from_ref(span));
+    }
+
+    fn shutdown(&self) {
+        self.exporter.lock().unwrap().shutdown();
+    }
+}
+
+/// Tuning of a `BatchSpanProcessor`.
+///
+/// [Batching processor spec](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/sdk-tracing.md#batching-processor)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchConfig {
+    /// Longest time a span waits before being exported.
+    pub schedule_delay: Duration,
+    /// Spans ending while this many are waiting are dropped.
+    pub max_queue_size: usize,
+    pub max_export_batch_size: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            schedule_delay: Duration::from_millis(5000),
+            max_queue_size: 2048,
+            max_export_batch_size: 512,
+        }
+    }
+}
+
+enum Message {
+    Span(Box<SpanData>),
+    Shutdown,
+}
+
+/// Queues ended spans and exports them from a background thread, in batches of up to
+/// `max_export_batch_size` at least every `schedule_delay`.
+pub struct BatchSpanProcessor {
+    sender: Mutex<SyncSender<Message>>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl BatchSpanProcessor {
+    pub fn new<E: SpanExporter + Send + 'static>(exporter: E, config: BatchConfig) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(config.max_queue_size);
+        let worker = thread::spawn(move || {
+            let mut exporter = exporter;
+            let mut batch = Vec::new();
+            let mut deadline = Instant::now() + config.schedule_delay;
+            loop {
+                let timeout = deadline.saturating_duration_since(Instant::now());
+                let mut shutdown = match receiver.recv_timeout(timeout) {
+                    Ok(Message::Span(span)) => {
+                        batch.push(*span);
+                        false
+                    }
+                    Err(RecvTimeoutError::Timeout) => false,
+                    Ok(Message::Shutdown) | Err(RecvTimeoutError::Disconnected) => true,
+                };
+                if shutdown || batch.len() >= config.max_export_batch_size {
+                    // Everything already queued goes out with this batch.
+                    for message in receiver.try_iter() {
+                        match message {
+                            Message::Span(span) => batch.push(*span),
+                            Message::Shutdown => shutdown = true,
+                        }
+                    }
+                } else if Instant::now() < deadline {
+                    continue;
+                }
+                for chunk in batch.chunks(config.max_export_batch_size.max(1)) {
+                    exporter.export(chunk);
+                }
+                batch.clear();
+                deadline = Instant::now() + config.schedule_delay;
+                if shutdown {
+                    exporter.shutdown();
+                    return;
+                }
+            }
+        });
+        Self {
+            sender: Mutex::new(sender),
+            worker: Mutex::new(Some(worker)),
+        }
+    }
+}
+
+impl SpanProcessor for BatchSpanProcessor {
+    fn on_end(&self, span: &SpanData) {
+        let _ = self
+            .sender
+            .lock()
+            .unwrap()
+            .try_send(Message::Span(Box::new(span.clone())));
+    }
+
+    /// Exports the queued spans and waits for the exporter to shut down.
+    fn shutdown(&self) {
+        if let Some(worker) = self.worker.lock().unwrap().take() {
+            let _ = self.sender.lock().unwrap().send(Message::Shutdown);
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for BatchSpanProcessor {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[test]
+fn batch_processor_exports_in_batches() {
+    use crate::api::resources::Resource;
+    use crate::exporter::test_span_data;
+    use crate::sdk::trace::export::ResultCode;
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct Sizes(Arc<Mutex<Vec<usize>>>, Arc<Mutex<bool>>);
+
+    impl SpanExporter for Sizes {
+        fn export(&mut self, batch: &[SpanData]) -> ResultCode {
+            self.0.lock().unwrap().push(batch.len());
+            ResultCode::Success
+        }
+
+        fn shutdown(&mut self) {
+            *self.1.lock().unwrap() = true;
+        }
+    }
+
+    let exporter = Sizes::default();
+    let (sizes, is_shutdown) = (exporter.0.clone(), exporter.1.clone());
+    let processor = BatchSpanProcessor::new(
+        exporter,
+        BatchConfig {
+            schedule_delay: Duration::from_secs(60),
+            max_queue_size: 16,
+            max_export_batch_size: 2,
+        },
+    );
+    let r = Resource::default();
+    for i in 1..=5 {
+        processor.on_end(&test_span_data(&r, "a", i, None, 0, 1));
+    }
+    processor.shutdown();
+    let sizes = sizes.lock().unwrap();
+    assert_eq!(sizes.iter().sum::<usize>(), 5);
+    assert!(sizes.iter().all(|x| *x <= 2));
+    assert!(*is_shutdown.lock().unwrap());
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use crate::api::resources::Resource;
+use crate::api::trace::hooks::OnEnd;
+use crate::api::trace::in_memory::InMemoryTracer;
+use crate::api::trace::span_data::SpanData;
+use crate::api::trace::span_limits::SpanLimits;
+use crate::sdk::trace::processor::SpanProcessor;
+use crate::sdk::trace::sampler::Sampler;
+
+/// Resource, sampler, span limits and span processors shared by the tracers of a program.
+///
+/// [TracerProvider spec](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/sdk-tracing.md#tracer-provider)
+pub struct TracerProvider {
+    resource: Resource,
+    sampler: Sampler,
+    limits: SpanLimits,
+    processors: Vec<Arc<dyn SpanProcessor>>,
+}
+
+impl TracerProvider {
+    pub fn new(resource: Resource) -> Self {
+        Self {
+            resource,
+            sampler: Sampler::default(),
+            limits: SpanLimits::default(),
+            processors: Vec::new(),
+        }
+    }
+
+    pub fn with_sampler(self, sampler: Sampler) -> Self {
+        Self { sampler, ..self }
+    }
+
+    pub fn with_limits(self, limits: SpanLimits) -> Self {
+        Self { limits, ..self }
+    }
+
+    /// Processors are called in the order they are added.
+    pub fn with_processor(self, processor: Box<dyn SpanProcessor>) -> Self {
+        let mut processors = self.processors;
+        processors.push(Arc::from(processor));
+        Self { processors, ..self }
+    }
+
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    pub fn sampler(&self) -> &Sampler {
+        &self.sampler
+    }
+
+    pub fn limits(&self) -> &SpanLimits {
+        &self.limits
+    }
+
+    /// A tracer whose `new_root_context` and `new_child_context` apply the sampler, and whose
+    /// sampled spans are handed to every processor when they end.
+    pub fn tracer<'a, 'b>(&self) -> InMemoryTracer<'a, 'b> {
+        InMemoryTracer::new(self.resource.clone())
+            .with_limits(self.limits)
+            .with_sampler(Arc::new(self.sampler.clone()))
+            .with_on_end(Arc::new(Processors(self.processors.clone())))
+    }
+
+    /// Shuts the processors down, exporting the spans they still hold.
+    pub fn shutdown(&self) {
+        for processor in &self.processors {
+            processor.shutdown();
+        }
+    }
+}
+
+/// Hands each ended span to the processors in order.
+struct Processors(Vec<Arc<dyn SpanProcessor>>);
+
+impl OnEnd for Processors {
+    fn on_end(&self, span: &SpanData) {
+        for processor in &self.0 {
+            processor.on_end(span);
+        }
+    }
+}
+
+#[test]
+fn tracer_samples_and_processes_spans() {
+    use crate::api::trace::span_context::{SpanContext, TraceOption};
+    use crate::api::trace::{Span, SpanKind};
+    use crate::exporter::in_memory::InMemorySpanExporter;
+    use crate::sdk::trace::processor::SimpleSpanProcessor;
+
+    let exporter = InMemorySpanExporter::new();
+    let provider = TracerProvider::new(Resource::default())
+        .with_processor(Box::new(SimpleSpanProcessor::new(exporter.clone())));
+    let tracer = provider.tracer();
+
+    let root = tracer.new_root_context();
+    let root = SpanContext::new(
+        &root.trace_id,
+        root.span_id,
+        root.trace_option,
+        root.trace_state,
+    );
+    assert!(root.is_sample());
+    let mut child = tracer.start_span(
+        tracer.new_child_context(&root),
+        "child",
+        SpanKind::INTERNAL,
+        Some(&root.span_id),
+    );
+    child.end();
+    child.end();
+
+    let mut remote = tracer.new_child_context(&root);
+    remote.trace_option = TraceOption::empty();
+    let mut unsampled = tracer.start_span(
+        tracer.new_child_context(&remote),
+        "unsampled",
+        SpanKind::INTERNAL,
+        Some(&remote.span_id),
+    );
+    unsampled.end();
+
+    let names: Vec<String> = exporter
+        .finished_spans()
+        .iter()
+        .map(|x| x.name().to_owned())
+        .collect();
+    assert_eq!(names, vec!["child"]);
+    provider.shutdown();
+    assert!(exporter.is_shutdown());
+}
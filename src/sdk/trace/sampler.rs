@@ -0,0 +1,78 @@
+use crate::api::trace::hooks::ShouldSample;
+use crate::api::trace::span_context::{SpanContext, TraceId};
+
+/// Decides whether a new span is sampled, i.e. recorded and exported.
+///
+/// [Sampler spec](https://github.com/open-telemetry/opentelemetry-specification/blob/master/specification/sdk-tracing.md#sampler)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sampler {
+    AlwaysOn,
+    AlwaysOff,
+    /// Samples this fraction of traces, decided from the trace id so every span of a trace agrees.
+    TraceIdRatio(f64),
+    /// Follows the parent's decision, and uses the inner sampler for root spans.
+    ParentBased(Box<Sampler>),
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Sampler::ParentBased(Box::new(Sampler::AlwaysOn))
+    }
+}
+
+impl Sampler {
+    pub fn should_sample(&self, parent: Option<&SpanContext>, trace_id: &TraceId) -> bool {
+        match self {
+            Sampler::AlwaysOn => true,
+            Sampler::AlwaysOff => false,
+            Sampler::TraceIdRatio(ratio) => {
+                if *ratio >= 1.0 {
+                    return true;
+                }
+                // Lower 64 bits of the id, which are random for W3C trace ids.
+                let threshold = (ratio.max(0.0) * u64::MAX as f64) as u64;
+                (trace_id.as_u128() as u64) < threshold
+            }
+            Sampler::ParentBased(root) => match parent {
+                Some(parent) => parent.is_sample(),
+                None => root.should_sample(None, trace_id),
+            },
+        }
+    }
+}
+
+impl ShouldSample for Sampler {
+    fn should_sample(&self, parent: Option<&SpanContext>, trace_id: &TraceId) -> bool {
+        Sampler::should_sample(self, parent, trace_id)
+    }
+}
+
+#[test]
+fn samplers_decide_from_parent_and_trace_id() {
+    use crate::api::trace::span_context::{SpanId, TraceOption, TraceState};
+    use std::num::{NonZeroU128, NonZeroU64};
+
+    let low = TraceId::new(NonZeroU128::new(1 << 64 | 1).unwrap());
+    let high = TraceId::new(NonZeroU128::new(u64::MAX as u128).unwrap());
+    let ratio = Sampler::TraceIdRatio(0.5);
+    assert!(ratio.should_sample(None, &low));
+    assert!(!ratio.should_sample(None, &high));
+    assert!(!Sampler::TraceIdRatio(0.0).should_sample(None, &low));
+    assert!(Sampler::TraceIdRatio(1.0).should_sample(None, &high));
+
+    let span_id = || SpanId::new(NonZeroU64::new(1).unwrap());
+    let sampled = SpanContext::new(
+        &low,
+        span_id(),
+        TraceOption::MASK_SAMPLE,
+        TraceState::empty(),
+    );
+    let unsampled = SpanContext::new(&low, span_id(), TraceOption::empty(), TraceState::empty());
+    let parent_based = Sampler::default();
+    assert!(parent_based.should_sample(None, &low));
+    assert!(parent_based.should_sample(Some(&sampled), &low));
+    assert!(!parent_based.should_sample(Some(&unsampled), &low));
+    let off = Sampler::ParentBased(Box::new(Sampler::AlwaysOff));
+    assert!(!off.should_sample(None, &low));
+    assert!(off.should_sample(Some(&sampled), &low));
+}
@@ -0,0 +1,158 @@
+//! Request rate, errors and duration (RED) metrics derived from ended spans.
+
+use std::str::FromStr;
+
+use crate::api::metrics::instrument::{Counter, Histogram};
+use crate::api::metrics::labels::LabelSet;
+use crate::api::metrics::Meter;
+use crate::api::registry::Variable;
+use crate::api::resources::{LabelName, LabelValue};
+use crate::api::trace::span_context::{SpanContext, TraceState};
+use crate::api::trace::span_data::SpanData;
+use crate::api::unit::Unit;
+use crate::sdk::trace::processor::SpanProcessor;
+
+pub const CALLS: &str = "span.calls";
+pub const ERRORS: &str = "span.errors";
+pub const DURATION: &str = "span.duration";
+
+/// Counts every ended span, counts those with a non-OK `Status`, and records their
+/// duration in milliseconds, labelled by `span.name`, `span.kind` and the chosen attributes.
+///
+/// Durations of sampled spans are recorded in the span, so they may keep exemplars.
+pub struct SpanMetricsProcessor {
+    calls: Counter,
+    errors: Counter,
+    duration: Histogram,
+    attributes: Vec<(String, LabelName)>,
+}
+
+impl SpanMetricsProcessor {
+    pub fn new(meter: &Meter) -> Self {
+        let spans = || Unit::Annotation("spans".to_owned());
+        Self {
+            calls: meter
+                .new_counter(Variable::new(CALLS, spans()).with_description("Ended spans.")),
+            errors: meter.new_counter(
+                Variable::new(ERRORS, spans())
+                    .with_description("Ended spans with an error status."),
+            ),
+            duration: meter.new_histogram(
+                Variable::new(DURATION, Unit::Milliseconds)
+                    .with_description("Duration of ended spans."),
+            ),
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Adds these span attributes as labels when present; unusable names are skipped.
+    pub fn with_attributes(self, keys: &[&str]) -> Self {
+        let mut attributes = self.attributes;
+        attributes.extend(
+            keys.iter()
+                .filter_map(|k| LabelName::from_str(k).ok().map(|n| ((*k).to_owned(), n))),
+        );
+        Self { attributes, ..self }
+    }
+
+    /// Labels with values that are not valid label values are left out.
+    fn labels(&self, span: &SpanData) -> LabelSet {
+        let fixed = vec![
+            ("span.name", span.name().to_owned()),
+            ("span.kind", format!("{:?}", span.kind())),
+        ]
+        .into_iter()
+        .map(|(k, v)| (LabelName::from_str(k).unwrap(), v));
+        let attributes = self.attributes.iter().filter_map(|(key, name)| {
+            span.attributes()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| (name.clone(), v.to_string()))
+        });
+        fixed
+            .chain(attributes)
+            .filter_map(|(k, v)| LabelValue::from_str(&v).ok().map(|v| (k, v)))
+            .collect()
+    }
+}
+
+impl SpanProcessor for SpanMetricsProcessor {
+    fn on_end(&self, span: &SpanData) {
+        let labels = self.labels(span);
+        self.calls.add(1.0, &labels);
+        if !span.status().is_ok() {
+            self.errors.add(1.0, &labels);
+        }
+        if let Some(d) = span.end_time().duration_since(span.start_time()) {
+            let context = SpanContext::new(
+                span.trace_id(),
+                span.span_id().clone(),
+                span.trace_option(),
+                TraceState::empty(),
+            );
+            self.duration
+                .record_in_span(d.as_secs_f64() * 1000.0, &labels, &context);
+        }
+    }
+}
+
+#[test]
+fn span_metrics_from_ended_spans() {
+    use crate::api::resources::Resource;
+    use crate::api::trace::key::Value;
+    use crate::api::trace::status::Status;
+    use crate::api::trace::SpanKind;
+    use crate::exporter::test_span_data;
+    use crate::sdk::metrics::aggregator::{Aggregation, SimpleSelector};
+    use crate::sdk::metrics::Accumulator;
+    use std::sync::Arc;
+
+    let accumulator = Arc::new(
+        Accumulator::new().with_selector(Box::new(SimpleSelector::new().with_min_max_sum_count())),
+    );
+    let processor = SpanMetricsProcessor::new(&Meter::new(accumulator.clone()))
+        .with_attributes(&["http.method"]);
+
+    let r = Resource::default();
+    let mut ok = test_span_data(&r, "GET /", 1, None, 1_000, 3_000);
+    ok.kind = SpanKind::SERVER;
+    ok.attributes
+        .insert("http.method".to_owned(), Value::String("GET".to_owned()));
+    let mut failed = ok.clone();
+    failed.end_time = ok
+        .start_time
+        .checked_add(std::time::Duration::from_millis(8))
+        .unwrap();
+    failed.status = Status::internal();
+    processor.on_end(&ok);
+    processor.on_end(&failed);
+
+    let labels = LabelSet::try_from_pairs(&[
+        ("http.method", "GET"),
+        ("span.kind", "SERVER"),
+        ("span.name", "GET /"),
+    ])
+    .unwrap();
+    let mut records = accumulator.collect();
+    records.sort_by(|a, b| a.descriptor().name().cmp(b.descriptor().name()));
+    let summary: Vec<(&str, &LabelSet, &Aggregation)> = records
+        .iter()
+        .map(|x| (x.descriptor().name(), x.labels(), x.aggregation()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (CALLS, &labels, &Aggregation::Sum(2.0)),
+            (
+                DURATION,
+                &labels,
+                &Aggregation::MinMaxSumCount {
+                    min: 2.0,
+                    max: 8.0,
+                    sum: 10.0,
+                    count: 2
+                }
+            ),
+            (ERRORS, &labels, &Aggregation::Sum(1.0)),
+        ]
+    );
+}
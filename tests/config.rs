@@ -0,0 +1,90 @@
+use std::env;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+use ot_rs::api::resources::Resource;
+use ot_rs::api::trace::span_context::{SpanContext, TraceOption};
+use ot_rs::api::trace::{Span, SpanKind};
+use ot_rs::sdk::config::EnvConfig;
+
+/// Accepts one Zipkin POST, answers 202 and returns its body.
+fn receive_one(listener: TcpListener) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some(x) = line.to_lowercase().strip_prefix("content-length:") {
+                length = x.trim().parse().unwrap();
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        reader
+            .get_mut()
+            .write_all(b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        String::from_utf8(body).unwrap()
+    })
+}
+
+#[test]
+fn env_configured_provider_exports_sampled_spans() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!(
+        "http://127.0.0.1:{}/api/v2/spans",
+        listener.local_addr().unwrap().port()
+    );
+    let received = receive_one(listener);
+    env::set_var("OTEL_TRACES_SAMPLER", "parentbased_always_on");
+    env::set_var("OTEL_TRACES_EXPORTER", "zipkin");
+    env::set_var("OTEL_EXPORTER_ZIPKIN_ENDPOINT", &endpoint);
+    env::set_var("OTEL_BSP_MAX_EXPORT_BATCH_SIZE", "16");
+
+    let config = EnvConfig::from_env();
+    assert!(config.warnings().is_empty(), "{:?}", config.warnings());
+    let provider = config.tracer_provider(Resource::default()).unwrap();
+    let tracer = provider.tracer();
+
+    let root = tracer.new_root_context();
+    let root = SpanContext::new(
+        &root.trace_id,
+        root.span_id,
+        root.trace_option,
+        root.trace_state,
+    );
+    let mut span = tracer.start_span(
+        tracer.new_child_context(&root),
+        "sampled",
+        SpanKind::SERVER,
+        Some(&root.span_id),
+    );
+    span.end();
+
+    let mut remote = tracer.new_child_context(&root);
+    remote.trace_option = TraceOption::empty();
+    let mut span = tracer.start_span(
+        tracer.new_child_context(&remote),
+        "not sampled",
+        SpanKind::SERVER,
+        Some(&remote.span_id),
+    );
+    span.end();
+
+    provider.shutdown();
+    let body = received.join().unwrap();
+    let spans: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let names: Vec<&str> = spans
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|x| x["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["sampled"]);
+}
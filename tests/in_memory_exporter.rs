@@ -0,0 +1,92 @@
+use std::convert::TryFrom;
+
+use ot_rs::api::resources::Resource;
+use ot_rs::api::trace::in_memory::InMemorySpan;
+use ot_rs::api::trace::key::Value;
+use ot_rs::api::trace::span_context::{SpanContext, SpanId, TraceId, TraceOption, TraceState};
+use ot_rs::api::trace::span_data::SpanData;
+use ot_rs::api::trace::{Span, SpanKind};
+use ot_rs::exporter::in_memory::{InMemorySpanExporter, SpanTree};
+use ot_rs::sdk::trace::export::{ResultCode, SpanExporter};
+
+fn finish(mut span: InMemorySpan) -> SpanData {
+    span.end();
+    SpanData::try_from(span).unwrap()
+}
+
+fn span<'a, 'b>(
+    trace_id: &'a TraceId,
+    resource: &'a Resource,
+    name: &str,
+    parent: Option<&'b SpanId>,
+) -> InMemorySpan<'a, 'b> {
+    InMemorySpan::new(
+        SpanContext::new(
+            trace_id,
+            SpanId::generate_random(),
+            TraceOption::MASK_SAMPLE,
+            TraceState::empty(),
+        ),
+        resource,
+        name,
+        SpanKind::INTERNAL,
+        parent,
+    )
+}
+
+#[test]
+fn collect_and_query() {
+    let t = TraceId::generate_random();
+    let r = Resource::default();
+    let root = span(&t, &r, "root", None);
+    let root_id = root.context().span_id.clone();
+    let mut child = span(&t, &r, "child", Some(&root_id));
+    child.set_attribute("http.status_code".to_owned(), Value::Int64(200));
+    let grandchild_parent = child.context().span_id.clone();
+    let grandchild = span(&t, &r, "grandchild", Some(&grandchild_parent));
+
+    let collector = InMemorySpanExporter::new();
+    let mut exporter = collector.clone();
+    let batch = vec![finish(grandchild), finish(child), finish(root)];
+    assert_eq!(exporter.export(&batch), ResultCode::Success);
+
+    assert_eq!(collector.finished_spans().len(), 3);
+    assert_eq!(collector.find_by_name("child").len(), 1);
+    assert_eq!(collector.find_by_trace_id(&t).len(), 3);
+    assert_eq!(collector.children_of(&root_id)[0].name(), "child");
+
+    collector.assert_tree(
+        &SpanTree::new("root")
+            .with_child(SpanTree::new("child").with_child(SpanTree::new("grandchild"))),
+    );
+    collector.assert_attribute("child", "http.status_code");
+    collector.assert_attribute_eq("child", "http.status_code", &Value::Int64(200));
+
+    collector.reset();
+    assert!(collector.finished_spans().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "no trace matches")]
+fn assert_tree_mismatch() {
+    let t = TraceId::generate_random();
+    let r = Resource::default();
+    let mut exporter = InMemorySpanExporter::new();
+    exporter.export(&[finish(span(&t, &r, "root", None))]);
+
+    exporter.assert_tree(&SpanTree::new("root").with_child(SpanTree::new("child")));
+}
+
+#[test]
+fn shutdown_rejects_export() {
+    let t = TraceId::generate_random();
+    let r = Resource::default();
+    let mut exporter = InMemorySpanExporter::new();
+    exporter.shutdown();
+
+    assert_eq!(
+        exporter.export(&[finish(span(&t, &r, "root", None))]),
+        ResultCode::FailedNotRetryable
+    );
+    assert!(exporter.finished_spans().is_empty());
+}
@@ -2,7 +2,9 @@ use std::str::FromStr;
 
 use proptest::prelude::*;
 
-use ot_rs::api::resources::{LabelName, LabelValue, Resource};
+use ot_rs::api::resources::{
+    LabelName, LabelValue, MergeError, MergePolicy, Resource, ResourceValue,
+};
 
 proptest! {
     #[test]
@@ -61,8 +63,95 @@ fn merge_test() {
     let z = x.merge(&y);
     assert!(z
         .labels()
-        .any(|(k, v)| k.value() == "a" && v.value() == "1"));
+        .any(|(k, v)| k.value() == "a" && v.as_str() == Some("1")));
     assert!(z
         .labels()
-        .any(|(k, v)| k.value() == "b" && v.value() == "2"));
+        .any(|(k, v)| k.value() == "b" && v.as_str() == Some("2")));
+}
+
+#[test]
+fn try_merge_policies() {
+    let mut primary = Resource::default();
+    primary
+        .try_upsert("service.name", "cart")
+        .unwrap()
+        .try_upsert("process.pid", 7)
+        .unwrap();
+    let mut secondary = Resource::default();
+    secondary
+        .try_upsert("service.name", "shop")
+        .unwrap()
+        .try_upsert("process.pid", 7)
+        .unwrap()
+        .try_upsert("host.arch", "amd64")
+        .unwrap();
+
+    let a = primary
+        .try_merge(&secondary, MergePolicy::PrimaryWins)
+        .unwrap();
+    assert_eq!(a.get("service.name"), Some(&ResourceValue::from("cart")));
+    assert_eq!(a.get("host.arch"), Some(&ResourceValue::from("amd64")));
+    let b = primary
+        .try_merge(&secondary, MergePolicy::SecondaryWins)
+        .unwrap();
+    assert_eq!(b.get("service.name"), Some(&ResourceValue::from("shop")));
+    assert_eq!(b, primary.merge(&secondary));
+    assert_eq!(
+        primary.try_merge(&secondary, MergePolicy::ErrorOnConflict),
+        Err(MergeError::Conflict(
+            LabelName::from_str("service.name").unwrap(),
+            ResourceValue::from("cart"),
+            ResourceValue::from("shop"),
+        ))
+    );
+    secondary.upsert(LabelName::from_str("service.name").unwrap(), "cart");
+    assert!(primary
+        .try_merge(&secondary, MergePolicy::ErrorOnConflict)
+        .is_ok());
+}
+
+#[test]
+fn schema_urls_must_match() {
+    let v1 = Resource::default().with_schema_url("https://opentelemetry.io/schemas/1.0.0");
+    let v2 = Resource::default().with_schema_url("https://opentelemetry.io/schemas/2.0.0");
+    let none = Resource::default();
+
+    assert!(v1.try_merge(&v2, MergePolicy::SecondaryWins).is_err());
+    assert_eq!(
+        none.try_merge(&v1, MergePolicy::SecondaryWins)
+            .unwrap()
+            .schema_url(),
+        v1.schema_url()
+    );
+    assert_eq!(v1.merge(&v2).schema_url(), None);
+    assert_ne!(v1, none);
+}
+
+#[test]
+fn resources_hash_by_typed_values() {
+    use std::collections::HashSet;
+
+    let mut a = Resource::default();
+    a.try_upsert("ratio", 0.5)
+        .unwrap()
+        .try_upsert("ok", true)
+        .unwrap();
+    let mut b = Resource::default();
+    b.try_upsert("ok", true)
+        .unwrap()
+        .try_upsert("ratio", 0.5)
+        .unwrap();
+    let mut c = Resource::default();
+    c.try_upsert("ok", "true")
+        .unwrap()
+        .try_upsert("ratio", 0.5)
+        .unwrap();
+
+    let set: HashSet<Resource> = vec![a.clone(), b, c].into_iter().collect();
+    assert_eq!(set.len(), 2);
+    assert_eq!(
+        a.get("ratio").map(ToString::to_string),
+        Some("0.5".to_owned())
+    );
+    assert!(a.try_upsert("", 1).is_err());
 }
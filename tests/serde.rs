@@ -0,0 +1,70 @@
+#![cfg(feature = "serde")]
+
+use std::num::{NonZeroU128, NonZeroU64};
+
+use ot_rs::api::resources::Resource;
+use ot_rs::api::trace::key::Value;
+use ot_rs::api::trace::span_context::{SpanId, TraceId, TraceOption, TraceState};
+use ot_rs::api::trace::status::Status;
+use ot_rs::api::trace::trace_context::TraceContext;
+use serde_json::json;
+
+#[test]
+fn trace_context_round_trip() {
+    let t = TraceContext::new_without_trace_state(
+        TraceId::new(NonZeroU128::new(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736).unwrap()),
+        SpanId::new(NonZeroU64::new(0x00f0_67aa_0ba9_02b7).unwrap()),
+        TraceOption::MASK_SAMPLE,
+    );
+    let j = serde_json::to_value(&t).unwrap();
+    assert_eq!(
+        j,
+        json!({
+            "trace_id": "4bf92f3577b34da6a3ce929d0e0e4736",
+            "span_id": "00f067aa0ba902b7",
+            "trace_option": "01",
+            "trace_state": "",
+        })
+    );
+
+    let a: TraceContext = serde_json::from_value(j).unwrap();
+    assert_eq!(a.trace_id, t.trace_id);
+    assert_eq!(a.span_id, t.span_id);
+    assert_eq!(a.trace_option, t.trace_option);
+    assert_eq!(a.trace_state, TraceState::empty());
+}
+
+#[test]
+fn invalid_ids_rejected() {
+    assert!(serde_json::from_str::<TraceId>(r#""00000000000000000000000000000000""#).is_err());
+    assert!(serde_json::from_str::<SpanId>(r#""00F067AA0BA902B7""#).is_err());
+}
+
+#[test]
+fn resource_value_status_round_trip() {
+    let mut r = Resource::default();
+    r.try_upsert("service.name", "worker").unwrap();
+    let r = r.with_schema_url("https://opentelemetry.io/schemas/1.9.0");
+    let j = serde_json::to_value(&r).unwrap();
+    assert_eq!(
+        j,
+        json!({
+            "labels": {"service.name": "worker"},
+            "schema_url": "https://opentelemetry.io/schemas/1.9.0",
+        })
+    );
+    let a: Resource = serde_json::from_value(j).unwrap();
+    assert_eq!(
+        a.get("service.name").and_then(|x| x.as_str()),
+        Some("worker")
+    );
+    assert_eq!(a, r);
+
+    let v = Value::from(vec!["a", "b"]);
+    let a: Value = serde_json::from_str(&serde_json::to_string(&v).unwrap()).unwrap();
+    assert_eq!(a, v);
+
+    let s = Status::not_found().with_description("no such job".to_owned());
+    let a: Status = serde_json::from_str(&serde_json::to_string(&s).unwrap()).unwrap();
+    assert_eq!(a, s);
+}
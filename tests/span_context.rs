@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use std::num::{NonZeroU128, NonZeroU64};
 
 use ot_rs::api::trace::span_context::{SpanContext, SpanId, TraceId, TraceOption, TraceState};
@@ -19,11 +20,9 @@ fn span_context_valid() {
         a.trace_id_str(),
         "0000000000000000000000000000002a".to_owned()
     );
-    assert_eq!(a.span_id.to_base16(), "2a00000000000000".to_owned());
-    assert_eq!(
-        a.trace_id.to_base16(),
-        "2a000000000000000000000000000000".to_owned()
-    )
+    assert_eq!(a.span_id.to_base16(), a.span_id_str());
+    assert_eq!(a.trace_id.to_base16(), a.trace_id_str());
+    assert_eq!(format!("{:x}", a.trace_id), a.trace_id_str());
 }
 
 #[test]
@@ -45,15 +44,52 @@ fn span_id_compare() {
 #[test]
 fn trace_id_convert_base16() {
     let e = TraceId::generate_random();
-    let a = TraceId::try_from_base16(e.to_base16().as_str());
-    assert!(a.is_some());
-    assert_eq!(e, a.unwrap())
+    let a = TraceId::try_from(e.to_base16().as_str());
+    assert!(a.is_ok());
+    assert_eq!(e, a.unwrap());
+    assert_eq!(Ok(e.clone()), e.to_string().parse());
 }
 
 #[test]
 fn span_id_convert_base16() {
     let e = SpanId::generate_random();
-    let a = SpanId::try_from_base16(e.to_base16().as_str());
-    assert!(a.is_some());
-    assert_eq!(e, a.unwrap())
+    let a = SpanId::try_from(e.to_base16().as_str());
+    assert!(a.is_ok());
+    assert_eq!(e, a.unwrap());
+    assert_eq!(Ok(e.clone()), e.to_string().parse());
+}
+
+#[test]
+fn id_bytes_are_big_endian() {
+    let t = TraceId::from_bytes([
+        0x4b, 0xf9, 0x2f, 0x35, 0x77, 0xb3, 0x4d, 0xa6, 0xa3, 0xce, 0x92, 0x9d, 0x0e, 0x0e, 0x47,
+        0x36,
+    ])
+    .unwrap();
+    assert_eq!(t.to_string(), "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert_eq!(TraceId::from_bytes(t.to_bytes()), Some(t));
+
+    let s = SpanId::from_bytes([0x00, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7]).unwrap();
+    assert_eq!(s.to_string(), "00f067aa0ba902b7");
+    assert_eq!(SpanId::from_bytes(s.to_bytes()), Some(s));
+
+    assert_eq!(TraceId::from_bytes([0; 16]), None);
+    assert_eq!(SpanId::from_bytes([0; 8]), None);
+}
+
+#[test]
+fn id_validity() {
+    assert!(TraceId::is_valid("4bf92f3577b34da6a3ce929d0e0e4736"));
+    assert!(!TraceId::is_valid("00000000000000000000000000000000"));
+    assert!(!TraceId::is_valid("4BF92F3577B34DA6A3CE929D0E0E4736"));
+    assert!(!TraceId::is_valid("4bf92f3577b34da6a3ce929d0e0e473"));
+    assert!(!TraceId::is_valid("+bf92f3577b34da6a3ce929d0e0e4736"));
+    assert!(SpanId::is_valid("00f067aa0ba902b7"));
+    assert!(!SpanId::is_valid("0000000000000000"));
+    assert!(!SpanId::is_valid("00f067aa0ba902b7a"));
+    assert_eq!(
+        TraceOption::try_from_base16("01"),
+        Some(TraceOption::MASK_SAMPLE)
+    );
+    assert_eq!(TraceOption::try_from_base16("1"), None);
 }